@@ -0,0 +1,6 @@
+//! Benchmarks every scaffolded solution. Run with `cargo bench`.
+include!(concat!(env!("OUT_DIR"), "/days.rs"));
+
+fn main() {
+    advent_of_code::template::harness::main(DAYS);
+}
@@ -0,0 +1,48 @@
+//! Generates the registry of scaffolded solutions used by the benchmark harness in `benches/`.
+use std::{env, fs, path::Path};
+
+fn main() {
+    println!("cargo:rerun-if-changed=src/bin");
+
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let bin_dir = Path::new(&manifest_dir).join("src").join("bin");
+
+    let mut days: Vec<(u8, String)> = fs::read_dir(&bin_dir)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .filter_map(|entry| {
+                    let path = entry.path();
+                    let day = path.file_stem()?.to_str()?.parse::<u8>().ok()?;
+                    let is_rust = path.extension().is_some_and(|ext| ext == "rs");
+                    (is_rust && (1..=25).contains(&day))
+                        .then(|| (day, path.to_string_lossy().into_owned()))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    days.sort_unstable();
+
+    let mut registry = String::new();
+
+    for (day, path) in &days {
+        registry.push_str(&format!(
+            "#[path = {path:?}]\n#[allow(unused)]\nmod day_{day:02};\n\n"
+        ));
+    }
+
+    registry.push_str(
+        "/// Every scaffolded solution.\n\
+         pub const DAYS: &[advent_of_code::template::runner::Solution] = &[\n",
+    );
+
+    for (day, _) in &days {
+        registry.push_str(&format!("    day_{day:02}::SOLUTION,\n"));
+    }
+
+    registry.push_str("];\n");
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    fs::write(Path::new(&out_dir).join("days.rs"), registry).unwrap();
+}
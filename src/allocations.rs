@@ -0,0 +1,169 @@
+//! Global allocators and allocation counting for tests that assert a code path does not allocate.
+//!
+//! The global allocator is registered here rather than in each solution,
+//! so that every binary linking this library (including the benchmark harness) installs exactly one.
+//!
+//! Enable the `count-allocs` feature to install [`CountingAlloc`] as the global allocator of every solution,
+//! then wrap the code under test in [`count_allocations`] or [`assert_allocations_at_most`]:
+//!
+//! ```ignore
+//! #[cfg(feature = "count-allocs")]
+//! #[test]
+//! fn test_part_one_allocations() {
+//!     let input = advent_of_code::template::read_file("examples", DAY);
+//!     advent_of_code::allocations::assert_allocations_at_most(2, || part_one(&input));
+//! }
+//! ```
+//!
+//! Allocations are counted per thread, so tests running in parallel don't affect each other.
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+#[cfg(feature = "dhat-heap")]
+#[global_allocator]
+static ALLOC: dhat::Alloc = dhat::Alloc;
+
+#[cfg(all(feature = "count-allocs", not(feature = "dhat-heap")))]
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
+
+/// A global allocator that wraps [`System`] and counts allocations made inside a counting scope.
+pub struct CountingAlloc;
+
+/// Allocations made while running a closure.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Allocations {
+    /// Number of allocations, including reallocations.
+    pub count: u64,
+    /// Number of bytes requested.
+    pub bytes: u64,
+}
+
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    // NOTE: const initializers don't allocate and don't register destructors,
+    // which makes them safe to access from within the allocator.
+    static ACTIVE: Cell<bool> = const { Cell::new(false) };
+    static COUNT: Cell<u64> = const { Cell::new(0) };
+    static BYTES: Cell<u64> = const { Cell::new(0) };
+}
+
+fn record(size: usize) {
+    INSTALLED.store(true, Ordering::Relaxed);
+
+    let _ = ACTIVE.try_with(|active| {
+        if active.get() {
+            COUNT.with(|c| c.set(c.get() + 1));
+            BYTES.with(|b| b.set(b.get() + size as u64));
+        }
+    });
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        record(layout.size());
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        record(new_size);
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+    }
+}
+
+/// Returns `true` if [`CountingAlloc`] is the global allocator of the running program.
+pub fn is_installed() -> bool {
+    // NOTE: every program allocates before reaching user code, so the flag is set by then.
+    INSTALLED.load(Ordering::Relaxed)
+}
+
+/// Run `f` and count the allocations it makes on the current thread.
+///
+/// Scopes can be nested: allocations of an inner scope are also counted in the outer scope.
+///
+/// # Panics
+/// Panics if [`CountingAlloc`] is not installed as global allocator.
+pub fn count_allocations<R>(f: impl FnOnce() -> R) -> (R, Allocations) {
+    assert!(
+        is_installed(),
+        "`CountingAlloc` is not the global allocator. Enable the `count-allocs` feature."
+    );
+
+    let was_active = ACTIVE.with(|a| a.replace(true));
+    let count_before = COUNT.with(|c| c.replace(0));
+    let bytes_before = BYTES.with(|b| b.replace(0));
+
+    let result = f();
+
+    ACTIVE.with(|a| a.set(was_active));
+    let count = COUNT.with(|c| c.replace(count_before + c.get()));
+    let bytes = BYTES.with(|b| b.replace(bytes_before + b.get()));
+
+    (result, Allocations { count, bytes })
+}
+
+/// Run `f` and assert that it makes at most `max` allocations on the current thread.
+///
+/// # Panics
+/// Panics if `f` allocates more than `max` times, or if [`CountingAlloc`] is not installed.
+pub fn assert_allocations_at_most<R>(max: u64, f: impl FnOnce() -> R) -> R {
+    let (result, allocations) = count_allocations(f);
+    assert!(
+        allocations.count <= max,
+        "expected at most {max} allocation(s), got {} ({} bytes)",
+        allocations.count,
+        allocations.bytes
+    );
+    result
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    #[cfg(all(feature = "count-allocs", not(feature = "dhat-heap")))]
+    mod counting {
+        use crate::allocations::{assert_allocations_at_most, count_allocations};
+
+        #[test]
+        fn counts_allocations() {
+            let (v, allocations) = count_allocations(|| vec![1_u64, 2, 3]);
+            assert_eq!(v.len(), 3);
+            assert_eq!(allocations.count, 1);
+            assert_eq!(allocations.bytes, 24);
+        }
+
+        #[test]
+        fn counts_nested_scopes() {
+            let ((_, inner), outer) = count_allocations(|| {
+                let _a = Box::new(1_u8);
+                count_allocations(|| Box::new(2_u8))
+            });
+            assert_eq!(inner.count, 1);
+            assert_eq!(outer.count, 2);
+        }
+
+        #[test]
+        fn passes_for_non_allocating_code() {
+            let sum = assert_allocations_at_most(0, || (1..=10).sum::<u32>());
+            assert_eq!(sum, 55);
+        }
+
+        #[test]
+        #[should_panic]
+        fn panics_for_allocating_code() {
+            assert_allocations_at_most(0, || String::from("xmas"));
+        }
+    }
+}
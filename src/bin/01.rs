@@ -1,4 +1,6 @@
-advent_of_code::solution!(1);
+advent_of_code::solution!(1, generator: generate);
+use advent_of_code::counter::Counter;
+use advent_of_code::rng::Rng;
 use itertools::Itertools;
 
 pub fn part_one(input: &str) -> Option<u32> {
@@ -14,13 +16,8 @@ pub fn part_one(input: &str) -> Option<u32> {
 
 pub fn part_two(input: &str) -> Option<u32> {
     let [first, second] = parse_input(input);
-    let result = first
-        .iter()
-        .map(|a| {
-            let occurences = second.iter().filter(|&b| a == b).count();
-            a * occurences as u32
-        })
-        .sum();
+    let occurrences: Counter<u32> = second.into_iter().collect();
+    let result = first.iter().map(|a| a * occurrences.get(a) as u32).sum();
     Some(result)
 }
 
@@ -41,6 +38,21 @@ fn parse_input(input: &str) -> [Vec<u32>; 2] {
     [first, second]
 }
 
+/// Generates `100 * size` pairs of five digit numbers.
+/// Numbers are drawn from a small pool, so that the right list contains repeats of the left one.
+fn generate(seed: u64, size: usize) -> String {
+    let mut rng = Rng::new(seed);
+    let pool: Vec<u32> = (0..10 * size).map(|_| rng.range(10_000..100_000)).collect();
+
+    (0..100 * size)
+        .map(|_| {
+            let a = rng.choose(&pool).unwrap();
+            let b = rng.choose(&pool).unwrap();
+            format!("{a}   {b}\n")
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
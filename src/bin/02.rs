@@ -1,4 +1,11 @@
-advent_of_code::solution!(2);
+advent_of_code::solution!(
+    2,
+    generator: generate,
+    alternatives: [2 => part_two_brute_force],
+);
+use advent_of_code::rng::Rng;
+use advent_of_code::sequences::is_valid_with_removals;
+use itertools::Itertools;
 
 pub fn part_one(input: &str) -> Option<u32> {
     let input = parse_input(input);
@@ -7,11 +14,27 @@ pub fn part_one(input: &str) -> Option<u32> {
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
+    let input = parse_input(input);
+    let count = input
+        .iter()
+        .filter(|row| is_safe_with_removals::<1>(row))
+        .count() as u32;
+    Some(count)
+}
+
+/// Reference implementation of part two, removing each level in turn.
+pub fn part_two_brute_force(input: &str) -> Option<u32> {
     let input = parse_input(input);
     let count = input.iter().filter(|row| is_any_subset_safe(row)).count() as u32;
     Some(count)
 }
 
+/// is_safe_with_removals returns true when the row is safe after removing at most `K` levels
+fn is_safe_with_removals<const K: usize>(row: &[i32]) -> bool {
+    is_valid_with_removals::<_, K>(row, |a, b| a < b && b - a <= 3)
+        || is_valid_with_removals::<_, K>(row, |a, b| a > b && a - b <= 3)
+}
+
 fn is_any_subset_safe(row: &[i32]) -> bool {
     row.iter().enumerate().any(|(i, _)| {
         let mut sub_row = row.to_vec();
@@ -44,6 +67,30 @@ fn parse_input(input: &str) -> Vec<Vec<i32>> {
         .collect()
 }
 
+/// Generates `10 * size` reports of five to eight levels.
+/// Levels mostly change by one to three in the same direction, so that many reports are safe after one removal.
+fn generate(seed: u64, size: usize) -> String {
+    let mut rng = Rng::new(seed);
+
+    (0..10 * size)
+        .map(|_| {
+            let direction = if rng.chance(0.5) { 1 } else { -1 };
+            let mut level: i32 = rng.range(20..80);
+            (0..rng.range(5..=8))
+                .map(|_| {
+                    level += if rng.chance(0.15) {
+                        rng.range(-5..=5)
+                    } else {
+                        direction * rng.range(1..=3)
+                    };
+                    level
+                })
+                .join(" ")
+        })
+        .map(|report| format!("{report}\n"))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -62,9 +109,38 @@ mod tests {
         assert_eq!(result[0].len(), 5);
     }
 
+    #[cfg(all(feature = "count-allocs", not(feature = "dhat-heap")))]
+    #[test]
+    fn test_is_safe_does_not_allocate() {
+        let row = [7, 6, 4, 2, 1];
+        let safe = advent_of_code::allocations::assert_allocations_at_most(0, || is_safe(&row));
+        assert!(safe);
+    }
+
+    #[cfg(all(feature = "count-allocs", not(feature = "dhat-heap")))]
+    #[test]
+    fn test_is_safe_with_removals_does_not_allocate() {
+        let row = [1, 3, 2, 4, 5];
+        let safe = advent_of_code::allocations::assert_allocations_at_most(0, || {
+            is_safe_with_removals::<1>(&row)
+        });
+        assert!(safe);
+    }
+
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(4));
     }
+
+    #[test]
+    fn test_part_two_brute_force() {
+        let result = part_two_brute_force(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Some(4));
+    }
+
+    #[test]
+    fn test_part_two_alternatives_agree() {
+        advent_of_code::template::differential::assert_agree(&SOLUTION, 20);
+    }
 }
@@ -1,125 +1,100 @@
 advent_of_code::solution!(3);
+use advent_of_code::scanner::{Pattern, Scanner};
+use advent_of_code::vm::{self, Effect, Machine, Register, Registers};
 
 #[derive(Debug, PartialEq)]
-enum Instructions {
-    Mul(usize),
-    Do(usize),
-    Dont(usize),
+enum Instruction {
+    Mul(u32, u32),
+    Do,
+    Dont,
+}
+
+/// An instruction with the registers it uses, resolved by [`run`].
+#[derive(Debug)]
+struct Op {
+    instruction: Instruction,
+    /// The sum of the products.
+    sum: Register,
+    /// Whether `mul` instructions are enabled, non-zero if they are.
+    enabled: Register,
+}
+
+impl vm::Instruction for Op {
+    fn execute(&self, registers: &mut Registers) -> Effect {
+        match self.instruction {
+            Instruction::Mul(a, b) if registers[self.enabled] != 0 => {
+                registers[self.sum] += i64::from(a) * i64::from(b);
+            }
+            Instruction::Mul(..) => {}
+            Instruction::Do => registers[self.enabled] = 1,
+            Instruction::Dont => registers[self.enabled] = 0,
+        }
+        Effect::Next
+    }
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
     let data = parse_input(input);
-    let instructions = find_instructions_in_str(&data);
-    let mul_locations = filter_mul(instructions);
-    let products = get_products_inside_mul(&data, &mul_locations);
-
-    let sum: u32 = products.iter().sum();
-    Some(sum)
+    let mut program = parse_instructions(&data);
+    // Part one ignores `do()` and `don't()`.
+    program.retain(|instruction| matches!(instruction, Instruction::Mul(..)));
+    Some(run(program))
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
     let data = parse_input(input);
-    let instructions = find_instructions_in_str(&data);
-    let mul_locations = filter_mul_with_dont(instructions);
-    let products = get_products_inside_mul(&data, &mul_locations);
-
-    let sum: u32 = products.iter().sum();
-    Some(sum)
+    Some(run(parse_instructions(&data)))
 }
 
-fn find_instructions_in_str(input: &str) -> Vec<Instructions> {
-    const MUL_PREFIX: &[u8] = b"mul(";
-    const DO_PREFIX: &[u8] = b"do(";
-    const DONT_PREFIX: &[u8] = b"don't(";
-
-    let max_len = MUL_PREFIX.len().max(DO_PREFIX.len()).max(DONT_PREFIX.len());
-    input
-        .as_bytes()
-        .windows(max_len)
-        .enumerate()
-        .filter_map(|(i, window)| {
-            if window.starts_with(MUL_PREFIX) {
-                Some(Instructions::Mul(i))
-            } else if window.starts_with(DO_PREFIX) {
-                Some(Instructions::Do(i))
-            } else if window.starts_with(DONT_PREFIX) {
-                Some(Instructions::Dont(i))
-            } else {
-                None
-            }
-        })
-        .collect()
-}
+/// Run the program with `mul` enabled, returning the sum of the products.
+fn run(program: Vec<Instruction>) -> u32 {
+    let mut registers = Registers::new();
+    let sum = registers.register("sum");
+    let enabled = registers.register("enabled");
+    registers[enabled] = 1;
 
-fn filter_mul_with_dont(input: Vec<Instructions>) -> Vec<usize> {
-    let mut dont_is_active = false;
-    let filterd = input
+    let program = program
         .into_iter()
-        .filter(|instruction| match instruction {
-            Instructions::Dont(_) => {
-                dont_is_active = true;
-                false
-            }
-            Instructions::Do(_) => {
-                dont_is_active = false;
-                false
-            }
-            _ => !dont_is_active,
+        .map(|instruction| Op {
+            instruction,
+            sum,
+            enabled,
         })
         .collect();
-    filter_mul(filterd)
+    let mut machine = Machine::new(program, registers);
+    machine.run();
+    machine.registers[sum] as u32
 }
 
-fn filter_mul(i: Vec<Instructions>) -> Vec<usize> {
-    i.iter()
-        .filter_map(|instruction| match instruction {
-            Instructions::Mul(i) => Some(*i),
-            _ => None,
+/// Find the `mul(X,Y)`, `do()` and `don't()` instructions in the corrupted memory, in order.
+/// The numbers of a `mul` have one to three digits.
+fn parse_instructions(input: &str) -> Vec<Instruction> {
+    const MUL: usize = 0;
+    const DO: usize = 1;
+    const DONT: usize = 2;
+
+    let scanner = Scanner::new([
+        Pattern::call("mul", 2).max_digits(3),
+        Pattern::literal("do()"),
+        Pattern::literal("don't()"),
+    ]);
+
+    scanner
+        .scan(input)
+        .map(|m| match m.pattern {
+            MUL => {
+                let [a, b] = m.args() else {
+                    unreachable!("mul has two arguments")
+                };
+                Instruction::Mul(*a as u32, *b as u32)
+            }
+            DO => Instruction::Do,
+            DONT => Instruction::Dont,
+            _ => unreachable!("unknown pattern"),
         })
         .collect()
 }
 
-fn parse_mul_expression(input: &str, start: usize) -> Option<u32> {
-    let after_mul = start + 4; // Position after "mul("
-    if after_mul >= input.len() {
-        return None;
-    }
-
-    // Extract the substring starting after "mul("
-    let remaining = &input[after_mul..];
-
-    // Find the position of the closing ')' relative to `remaining`
-    let closing_paren = remaining.find(')')?;
-    let inside = &remaining[..closing_paren];
-
-    // Split the inside by comma
-    let mut parts = inside.split(',').map(|s| s.trim());
-
-    // Parse the first number
-    let first_str = parts.next()?;
-    let first_number: u32 = first_str.parse().ok()?;
-
-    // Parse the second number
-    let second_str = parts.next()?;
-    let second_number: u32 = second_str.parse().ok()?;
-
-    // Ensure there are no extra parts
-    if parts.next().is_some() {
-        return None;
-    }
-
-    Some(first_number * second_number)
-}
-
-/// Extracts and multiplies the two numbers inside each "mul(a, b)" expression.
-/// Returns a vector of products.
-fn get_products_inside_mul(input: &str, locations: &[usize]) -> Vec<u32> {
-    locations
-        .iter()
-        .filter_map(|&start| parse_mul_expression(input, start))
-        .collect()
-}
-
 fn parse_input(input: &str) -> String {
     input.lines().collect::<Vec<&str>>().join("")
 }
@@ -153,23 +128,42 @@ ghi"#;
     }
 
     #[test]
-    fn test_find_instructions_in_str() {
-        let input = "mul(1, 2)do(3, 4)don't(5, 6)";
+    fn test_parse_instructions() {
+        let input = "mul(1,2)do()mul(1, 2)don't()mul(1234,5)";
         assert_eq!(
-            find_instructions_in_str(input),
-            vec![
-                Instructions::Mul(0),
-                Instructions::Do(9),
-                Instructions::Dont(17)
-            ]
+            parse_instructions(input),
+            vec![Instruction::Mul(1, 2), Instruction::Do, Instruction::Dont]
         );
     }
 
     #[test]
-    fn test_filter_do_and_donts() {
+    fn test_parse_instructions_at_end_of_input() {
+        let input = "xmul(2,4)do()";
+        assert_eq!(
+            parse_instructions(input),
+            vec![Instruction::Mul(2, 4), Instruction::Do]
+        );
+    }
+
+    #[test]
+    fn test_run_toggles_mul() {
+        let program = parse_instructions("don't()mul(2,3)do()mul(4,5)don't()do()mul(1,1)");
+        assert_eq!(run(program), 21);
+    }
+
+    #[test]
+    fn test_parse_instructions_with_donts() {
         let input = &advent_of_code::template::read_file_part("examples", DAY, 2);
-        let instructions = find_instructions_in_str(input);
-        let filtered = filter_mul_with_dont(instructions);
-        assert_eq!(filtered, vec![1, 64]);
+        assert_eq!(
+            parse_instructions(input),
+            vec![
+                Instruction::Mul(2, 4),
+                Instruction::Dont,
+                Instruction::Mul(5, 5),
+                Instruction::Mul(11, 8),
+                Instruction::Do,
+                Instruction::Mul(8, 5),
+            ]
+        );
     }
 }
@@ -1,10 +1,17 @@
-advent_of_code::solution!(5);
+advent_of_code::solution!(
+    5,
+    generator: generate,
+    alternatives: [2 => part_two_reference],
+);
+use advent_of_code::hash::{FastMap, FastSet};
+use advent_of_code::rng::Rng;
+use advent_of_code::span;
 use itertools::Itertools;
-use std::collections::{HashMap, HashSet, VecDeque};
-use std::time::Instant;
+use std::cmp::Ordering;
+use std::fmt::Display;
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let (rules, sequences) = parse_input(input);
+    let (rules, sequences) = span!("parse", parse_input(input));
     let valid_sequences = sequences
         .iter()
         .filter(|sequence| is_sorted(&rules, sequence))
@@ -14,133 +21,169 @@ pub fn part_one(input: &str) -> Option<u32> {
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
+    let (rules, sequences) = span!("parse", parse_input(input));
+    match sum_sorted_middles(&rules, &sequences) {
+        Ok(sum) => Some(sum),
+        Err(error) => {
+            eprintln!("{error}");
+            None
+        }
+    }
+}
+
+/// Sorts updates by swapping adjacent pages until no rule is broken, without checking the rules.
+pub fn part_two_reference(input: &str) -> Option<u32> {
     let (rules, sequences) = parse_input(input);
-    let now = Instant::now();
-    let _sorted_sequences: u32 = sequences
-        .iter()
+    let middles = sequences
+        .into_iter()
         .filter(|sequence| !is_sorted(&rules, sequence))
-        .map(|sequence| {
-            let s = sorted_sequence(&rules, sequence);
-            s[s.len() / 2]
-        })
-        .sum();
-    println!("Execution time: {:?}", now.elapsed());
+        .map(|mut sequence| {
+            while let Some(i) =
+                (1..sequence.len()).find(|&i| is_ordered(&rules, sequence[i], sequence[i - 1]))
+            {
+                sequence.swap(i - 1, i);
+            }
+            sequence[sequence.len() / 2]
+        });
+    Some(middles.sum())
+}
 
-    let now = Instant::now();
-    let sorted_with_graph = sequences
+/// Sums the middle pages of the updates that are not sorted, after sorting them.
+fn sum_sorted_middles(rules: &Rules, sequences: &Sequences) -> Result<u32, UpdateError> {
+    sequences
         .iter()
-        .filter(|sequence| !is_sorted(&rules, sequence))
-        .map(|sequence| {
-            let (adj, mut in_degree) = rules_to_graph(&rules, sequence);
-            let s = topological_sort(&adj, &mut in_degree);
-            s[s.len() / 2]
+        .enumerate()
+        .filter(|(_, sequence)| !is_sorted(rules, sequence))
+        .map(|(i, sequence)| {
+            let sorted = span!("sort", sort_sequence(rules, sequence));
+            sorted.map(|s| s[s.len() / 2]).map_err(|error| UpdateError {
+                update: i + 1,
+                error,
+            })
         })
-        .sum();
-    println!("Execution time: {:?}", now.elapsed());
-    Some(sorted_with_graph)
+        .sum()
 }
 
 /// Rules has as key the number and value a set of numbers that should be ordered
 /// after the key number.
-type Rules = HashMap<u32, HashSet<u32>>;
+type Rules = FastMap<u32, FastSet<u32>>;
 
 /// Sequences is a list of sequences of numbers.
 type Sequences = Vec<Vec<u32>>;
 
-/// Sort the sequence based on the rules.
-fn sorted_sequence(rules: &Rules, sequence: &[u32]) -> Vec<u32> {
-    let mut sequence = sequence.to_vec();
-    let mut seen = HashMap::new();
-    let mut i = 0;
-    while i < sequence.len() {
-        let a = sequence[i];
-        if let Some(set) = rules.get(&a) {
-            for b in set {
-                if let Some(j) = seen.get(b).copied() {
-                    let value = sequence.remove(j);
-                    sequence.push(value);
-                    seen.remove(b);
-                    for (_, v) in seen.iter_mut() {
-                        if *v > j {
-                            *v -= 1;
-                        }
-                    }
-                    i -= 1
-                }
+/// The rules do not order the pages of a sequence consistently.
+#[derive(Debug, PartialEq, Eq)]
+enum OrderError {
+    /// No rule orders the two pages.
+    Unordered(u32, u32),
+    /// Rules order the two pages both ways.
+    Contradiction(u32, u32),
+    /// Rules order the three pages in a cycle.
+    Cycle(u32, u32, u32),
+}
+
+impl Display for OrderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OrderError::Unordered(a, b) => write!(f, "no rule orders pages {a} and {b}"),
+            OrderError::Contradiction(a, b) => {
+                write!(f, "rules order pages {a} and {b} both ways")
+            }
+            OrderError::Cycle(a, b, c) => {
+                write!(f, "rules order pages {a}, {b} and {c} in a cycle")
             }
         }
-        seen.insert(a, i);
-        i += 1;
     }
-    sequence
 }
 
-/// Checks if a sequence is sorted based on the provided rules.
-fn is_sorted(rules: &Rules, sequence: &[u32]) -> bool {
-    sequence
-        .iter()
-        .tuple_windows()
-        .all(|(&a, &b)| is_ordered(rules, a, b))
+/// An update whose pages are not ordered consistently by the rules.
+#[derive(Debug, PartialEq, Eq)]
+struct UpdateError {
+    /// The number of the update, starting at one.
+    update: usize,
+    error: OrderError,
 }
 
-/// Determines if two numbers are in the correct order based on the rules.
-fn is_ordered(rules: &Rules, a: u32, b: u32) -> bool {
-    match rules.get(&a) {
-        Some(set) => set.contains(&b),
-        None => false,
+impl Display for UpdateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "update {}: {}", self.update, self.error)
     }
 }
 
-type Graph = HashMap<u32, HashSet<u32>>;
-type InDegree = HashMap<u32, usize>;
-
-fn rules_to_graph(rules: &Rules, sequence: &[u32]) -> (Graph, InDegree) {
-    let mut adj: HashMap<u32, HashSet<u32>> = HashMap::new();
-    let mut in_degree: HashMap<u32, usize> = HashMap::new();
-    let mut sequence_set: HashSet<u32> = HashSet::new();
-
-    // Initialize adjacency list and in-degree count
-    for &node in sequence {
-        adj.entry(node).or_default();
-        in_degree.entry(node).or_insert(0);
-        sequence_set.insert(node);
-    }
-
-    for (&from, destinations) in rules {
-        for &destination in destinations {
-            if sequence_set.contains(&from) && sequence_set.contains(&destination) {
-                adj.entry(from).or_default().insert(destination);
-                in_degree.entry(destination).and_modify(|x| *x += 1);
-            }
-        }
+/// Compares two pages based on the rules, `Equal` if no rule orders them.
+fn compare(rules: &Rules, a: u32, b: u32) -> Ordering {
+    if is_ordered(rules, a, b) {
+        Ordering::Less
+    } else if is_ordered(rules, b, a) {
+        Ordering::Greater
+    } else {
+        Ordering::Equal
     }
+}
 
-    (adj, in_degree)
+/// Sort the sequence based on the rules.
+/// The rules have to order all pages of the sequence consistently, otherwise sorting is ambiguous.
+fn sort_sequence(rules: &Rules, sequence: &[u32]) -> Result<Vec<u32>, OrderError> {
+    check_order(rules, sequence)?;
+    let mut sequence = sequence.to_vec();
+    sequence.sort_by(|&a, &b| compare(rules, a, b));
+    Ok(sequence)
 }
 
-fn topological_sort(adj: &Graph, in_degree: &mut InDegree) -> Vec<u32> {
-    let mut queue = VecDeque::new();
-    for (&node, &degree) in in_degree.iter() {
-        if degree == 0 {
-            queue.push_back(node);
+/// Checks that the rules form a total order on the pages of the sequence.
+fn check_order(rules: &Rules, sequence: &[u32]) -> Result<(), OrderError> {
+    for (&a, &b) in sequence.iter().tuple_combinations() {
+        match (is_ordered(rules, a, b), is_ordered(rules, b, a)) {
+            (false, false) => return Err(OrderError::Unordered(a, b)),
+            (true, true) => return Err(OrderError::Contradiction(a, b)),
+            _ => {}
         }
     }
 
-    let mut sorted = Vec::new();
+    // NOTE: every pair is ordered, so the order is total exactly if no page
+    // precedes the same number of other pages as another page does.
+    let preceded = sequence.iter().map(|&a| {
+        sequence
+            .iter()
+            .filter(|&&b| is_ordered(rules, a, b))
+            .count()
+    });
+    if preceded.duplicates().next().is_none() {
+        return Ok(());
+    }
 
-    while let Some(node) = queue.pop_front() {
-        sorted.push(node);
-        if let Some(neighbors) = adj.get(&node) {
-            for &neighbor in neighbors {
-                let degree = in_degree.get_mut(&neighbor).unwrap();
-                *degree -= 1;
-                if *degree == 0 {
-                    queue.push_back(neighbor);
-                }
+    let cycle = sequence
+        .iter()
+        .tuple_combinations()
+        .find(|&(&a, &b, &c)| {
+            (is_ordered(rules, a, b) && is_ordered(rules, b, c) && is_ordered(rules, c, a))
+                || (is_ordered(rules, a, c) && is_ordered(rules, c, b) && is_ordered(rules, b, a))
+        })
+        .map(|(&a, &b, &c)| {
+            if is_ordered(rules, a, b) {
+                OrderError::Cycle(a, b, c)
+            } else {
+                OrderError::Cycle(a, c, b)
             }
-        }
+        })
+        .expect("a tournament that is not transitive contains a cycle of three");
+    Err(cycle)
+}
+
+/// Checks if a sequence is sorted based on the provided rules.
+fn is_sorted(rules: &Rules, sequence: &[u32]) -> bool {
+    sequence
+        .iter()
+        .tuple_windows()
+        .all(|(&a, &b)| is_ordered(rules, a, b))
+}
+
+/// Determines if two numbers are in the correct order based on the rules.
+fn is_ordered(rules: &Rules, a: u32, b: u32) -> bool {
+    match rules.get(&a) {
+        Some(set) => set.contains(&b),
+        None => false,
     }
-    sorted
 }
 
 pub fn parse_input(input: &str) -> (Rules, Sequences) {
@@ -156,7 +199,7 @@ pub fn parse_input(input: &str) -> (Rules, Sequences) {
                 .expect("More than two values in input");
             (a, b)
         })
-        .fold(HashMap::new(), |mut acc, (a, b)| {
+        .fold(FastMap::default(), |mut acc: Rules, (a, b)| {
             acc.entry(a).or_default().insert(b);
             acc
         });
@@ -174,6 +217,36 @@ pub fn parse_input(input: &str) -> (Rules, Sequences) {
     (rules, sequences)
 }
 
+/// Generates an input with `5 + size` pages, ordered by rules between every pair of pages.
+/// Updates are odd-length subsets of the pages, some of which are kept in order.
+fn generate(seed: u64, size: usize) -> String {
+    let mut rng = Rng::new(seed);
+
+    let mut pages: Vec<u32> = (10..100).collect();
+    rng.shuffle(&mut pages);
+    pages.truncate((5 + size).min(pages.len()));
+
+    let mut rules: Vec<(u32, u32)> = pages.iter().copied().tuple_combinations().collect();
+    rng.shuffle(&mut rules);
+
+    let updates = (0..2 + size).map(|_| {
+        let len = 2 * rng.range(0..=(pages.len() - 1) / 2) + 1;
+        let mut update: Vec<u32> = pages.clone();
+        rng.shuffle(&mut update);
+        update.truncate(len);
+        if rng.chance(0.3) {
+            update.sort_unstable_by_key(|page| pages.iter().position(|p| p == page));
+        }
+        update.iter().join(",")
+    });
+
+    format!(
+        "{}\n\n{}\n",
+        rules.iter().map(|(a, b)| format!("{a}|{b}")).join("\n"),
+        updates.into_iter().join("\n")
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -197,4 +270,59 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(123));
     }
+
+    #[test]
+    fn test_sort_sequence() {
+        let input = &advent_of_code::template::read_file("examples", DAY);
+        let (rules, _) = parse_input(input);
+        assert_eq!(
+            sort_sequence(&rules, &[97, 13, 75, 29, 47]),
+            Ok(vec![97, 75, 47, 29, 13])
+        );
+    }
+
+    #[test]
+    fn test_sort_sequence_with_inconsistent_rules() {
+        let (rules, _) = parse_input("1|2\n2|3\n3|1\n2|4\n4|2\n\n1\n");
+        assert_eq!(
+            sort_sequence(&rules, &[1, 2, 5]),
+            Err(OrderError::Unordered(1, 5))
+        );
+        assert_eq!(
+            sort_sequence(&rules, &[2, 4]),
+            Err(OrderError::Contradiction(2, 4))
+        );
+        assert_eq!(
+            sort_sequence(&rules, &[3, 2, 1]),
+            Err(OrderError::Cycle(3, 1, 2))
+        );
+    }
+
+    #[test]
+    fn test_sum_sorted_middles_reports_update() {
+        let (rules, sequences) = parse_input("1|2\n2|3\n3|1\n\n1,2\n2,1,3\n");
+        let error = sum_sorted_middles(&rules, &sequences).unwrap_err();
+        assert_eq!(error.update, 2);
+        assert_eq!(
+            error.to_string(),
+            "update 2: rules order pages 2, 3 and 1 in a cycle"
+        );
+    }
+
+    #[test]
+    fn test_part_two_with_inconsistent_rules() {
+        let result = part_two("1|2\n2|3\n3|1\n\n2,1,3\n");
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn test_part_two_reference() {
+        let result = part_two_reference(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Some(123));
+    }
+
+    #[test]
+    fn test_part_two_alternatives_agree() {
+        advent_of_code::template::differential::assert_agree(&SOLUTION, 20);
+    }
 }
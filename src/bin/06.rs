@@ -1,172 +1,541 @@
-advent_of_code::solution!(6);
-use std::collections::HashSet;
-use std::ops::{Add, Sub};
+advent_of_code::solution!(
+    6,
+    generator: generate,
+    alternatives: [2 => part_two_reference],
+);
+use advent_of_code::rng::Rng;
+use std::num::NonZero;
+use std::thread;
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let (map, mut start, mut direction) = parse_input(input);
-    let visited = walk_with_obstruction(&map, &mut start, &mut direction, None)?;
-    Some(visited.len() as u32)
+    let lab = Lab::parse(input);
+    let entries = lab.walk()?;
+
+    advent_of_code::visualize! {
+        advent_of_code::visual::play(lab.path_frames(&entries));
+    }
+
+    Some(entries.len() as u32 + 1)
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let (map, start, direction) = parse_input(input);
-    let cyclic_paths = find_cyclic_paths(&map, start, direction);
+    let lab = Lab::parse(input);
+    let entries = lab.walk()?;
+
+    let threads = thread::available_parallelism().map_or(1, NonZero::get);
+    let chunk_size = entries.len().div_ceil(threads).max(1);
+
+    let loops = thread::scope(|scope| {
+        let handles: Vec<_> = entries
+            .chunks(chunk_size)
+            .map(|chunk| {
+                let lab = &lab;
+                scope.spawn(move || {
+                    let mut states = States::new(lab.walls.len() * 4);
+                    chunk
+                        .iter()
+                        .filter(|entry| {
+                            states.clear();
+                            lab.loops_with_obstruction(entry, &mut states)
+                        })
+                        .count()
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("probe thread should not panic"))
+            .sum::<usize>()
+    });
+
+    advent_of_code::visualize! {
+        advent_of_code::visual::show(&lab.obstructions_frame(&entries));
+    }
+
+    Some(loops as u32)
+}
+
+/// The original implementation of part two, which walks the whole map for every obstruction.
+pub fn part_two_reference(input: &str) -> Option<u32> {
+    let (map, start, direction) = reference::parse_input(input);
+    let cyclic_paths = reference::find_cyclic_paths(&map, start, direction)?;
     Some(cyclic_paths as u32)
 }
 
-/// Tile in the map
-#[derive(Debug, PartialEq, Clone, Copy)]
-enum Tile {
-    Empty,
-    Wall,
+/// Directions as indices, clockwise, so that turning right is adding one.
+const UP: usize = 0;
+const RIGHT: usize = 1;
+const DOWN: usize = 2;
+const LEFT: usize = 3;
+
+fn turn_right(direction: usize) -> usize {
+    (direction + 1) % 4
 }
 
-impl Tile {
-    fn from_char(c: char) -> Self {
-        match c {
-            '.' => Self::Empty,
-            '#' => Self::Wall,
-            _ => panic!("Invalid tile"),
-        }
-    }
+/// The map with tiles stored row by row.
+struct Lab {
+    width: usize,
+    height: usize,
+    walls: Vec<bool>,
+    start: usize,
+    direction: usize,
+    /// For each direction and tile, the last tile before the next wall in that direction.
+    /// `None` if the guard walks off the map instead.
+    jumps: [Vec<Option<usize>>; 4],
 }
 
-#[derive(Debug, PartialEq, Hash, Eq, Clone, Copy)]
-struct Point {
-    x: i32,
-    y: i32,
+/// The first time the guard enters a tile while walking the original path.
+struct Entry {
+    tile: usize,
+    /// The tile the guard entered from.
+    from: usize,
+    direction: usize,
 }
 
-impl Point {
-    fn new(x: i32, y: i32) -> Self {
-        Point { x, y }
+impl Lab {
+    fn parse(input: &str) -> Self {
+        let width = input.lines().next().map_or(0, str::len);
+        let mut start = 0;
+        let mut direction = UP;
+
+        let walls: Vec<bool> = input
+            .lines()
+            .flat_map(str::chars)
+            .enumerate()
+            .map(|(i, c)| match c {
+                '.' => false,
+                '#' => true,
+                '^' | 'v' | 'V' | '<' | '>' => {
+                    start = i;
+                    direction = match c {
+                        '^' => UP,
+                        '>' => RIGHT,
+                        '<' => LEFT,
+                        _ => DOWN,
+                    };
+                    false
+                }
+                _ => panic!("Invalid tile"),
+            })
+            .collect();
+
+        let mut lab = Self {
+            width,
+            height: walls.len() / width.max(1),
+            walls,
+            start,
+            direction,
+            jumps: Default::default(),
+        };
+        lab.jumps = [UP, RIGHT, DOWN, LEFT].map(|direction| lab.jump_table(direction));
+        lab
     }
 
-    /// Returns the tile at the given point from the map if valid.
-    fn get_in_map<'a>(&self, map: &'a Map) -> Option<&'a Tile> {
-        if self.x < 0 || self.y < 0 {
-            return None;
+    /// The neighbouring tile in `direction`, if it is on the map.
+    fn step(&self, tile: usize, direction: usize) -> Option<usize> {
+        let (x, y) = (tile % self.width, tile / self.width);
+        match direction {
+            UP => (y > 0).then(|| tile - self.width),
+            RIGHT => (x + 1 < self.width).then(|| tile + 1),
+            DOWN => (y + 1 < self.height).then(|| tile + self.width),
+            _ => (x > 0).then(|| tile - 1),
         }
-        let (x, y) = (self.x as usize, self.y as usize);
-        map.get(y)?.get(x)
     }
 
-    fn from_direction(c: char) -> Self {
-        match c {
-            '^' => Point::new(0, -1),
-            'v' => Point::new(0, 1),
-            '<' => Point::new(-1, 0),
-            '>' => Point::new(1, 0),
-            _ => panic!("Unexpected direction character: {}", c),
+    /// The number of steps from `from` in `direction` to reach `to`, if `to` lies ahead.
+    fn steps_between(&self, from: usize, to: usize, direction: usize) -> Option<usize> {
+        let (fx, fy) = (from % self.width, from / self.width);
+        let (tx, ty) = (to % self.width, to / self.width);
+        match direction {
+            UP => (fx == tx && ty <= fy).then(|| fy - ty),
+            RIGHT => (fy == ty && tx >= fx).then(|| tx - fx),
+            DOWN => (fx == tx && ty >= fy).then(|| ty - fy),
+            _ => (fy == ty && tx <= fx).then(|| fx - tx),
         }
     }
 
-    /// Rotates the point direction 90 degrees counter-clockwise.
-    fn rotate_ccw(&mut self) {
-        let (old_x, old_y) = (self.x, self.y);
-        self.x = -old_y;
-        self.y = old_x;
+    fn jump_table(&self, direction: usize) -> Vec<Option<usize>> {
+        let mut jumps = vec![None; self.walls.len()];
+
+        // NOTE: the tile ahead has to be resolved first, it has a lower index when moving up or left.
+        let tiles: Box<dyn Iterator<Item = usize>> = match direction {
+            UP | LEFT => Box::new(0..self.walls.len()),
+            _ => Box::new((0..self.walls.len()).rev()),
+        };
+
+        for tile in tiles {
+            jumps[tile] = match self.step(tile, direction) {
+                None => None,
+                Some(next) if self.walls[next] => Some(tile),
+                Some(next) => jumps[next],
+            };
+        }
+        jumps
     }
-}
 
-/// Implement addition for Point to simplify arithmetic
-impl Add for Point {
-    type Output = Point;
+    /// Walk the guard's original path tile by tile.
+    /// Returns where each tile apart from the start was first entered, or `None` if the guard never leaves.
+    fn walk(&self) -> Option<Vec<Entry>> {
+        let mut visited = vec![false; self.walls.len()];
+        let mut states = States::new(self.walls.len() * 4);
+        let mut entries = vec![];
+
+        let (mut tile, mut direction) = (self.start, self.direction);
+        visited[tile] = true;
 
-    fn add(self, other: Point) -> Point {
-        Point::new(self.x + other.x, self.y + other.y)
+        loop {
+            if !states.insert(tile * 4 + direction) {
+                return None;
+            }
+
+            match self.step(tile, direction) {
+                None => return Some(entries),
+                Some(next) if self.walls[next] => direction = turn_right(direction),
+                Some(next) => {
+                    if !visited[next] {
+                        visited[next] = true;
+                        entries.push(Entry {
+                            tile: next,
+                            from: tile,
+                            direction,
+                        });
+                    }
+                    tile = next;
+                }
+            }
+        }
     }
+
+    /// Returns `true` if the guard loops when an obstruction is placed on the entered tile.
+    /// The path up to the entry is unchanged, so the probe starts just before the obstruction
+    /// and jumps from wall to wall, recording only the states in which the guard turns.
+    fn loops_with_obstruction(&self, entry: &Entry, states: &mut States) -> bool {
+        let obstruction = entry.tile;
+        let (mut tile, mut direction) = (entry.from, entry.direction);
+
+        loop {
+            let jump = self.jumps[direction][tile];
+            let blocked = self
+                .steps_between(tile, obstruction, direction)
+                .filter(|&steps| steps > 0)
+                .is_some_and(|steps| {
+                    jump.is_none_or(|jump| {
+                        steps <= self.steps_between(tile, jump, direction).unwrap_or(0)
+                    })
+                });
+
+            tile = match (blocked, jump) {
+                (true, _) => self
+                    .step(obstruction, (direction + 2) % 4)
+                    .expect("the guard came from the tile before the obstruction"),
+                (false, Some(jump)) => jump,
+                (false, None) => return false,
+            };
+
+            if !states.insert(tile * 4 + direction) {
+                return true;
+            }
+            direction = turn_right(direction);
+        }
+    }
+}
+
+/// A set of (tile, direction) states in a flat bitset, which remembers the bits it set to be cleared cheaply.
+struct States {
+    bits: Vec<u64>,
+    set: Vec<usize>,
 }
 
-/// Implement subtraction for Point to simplify arithmetic
-impl Sub for Point {
-    type Output = Point;
+impl States {
+    fn new(len: usize) -> Self {
+        Self {
+            bits: vec![0; len.div_ceil(64)],
+            set: vec![],
+        }
+    }
+
+    /// Insert a state, returns `false` if it was already present.
+    fn insert(&mut self, state: usize) -> bool {
+        let (word, bit) = (state / 64, 1 << (state % 64));
+        if self.bits[word] & bit != 0 {
+            return false;
+        }
+        self.bits[word] |= bit;
+        self.set.push(word);
+        true
+    }
 
-    fn sub(self, other: Point) -> Point {
-        Point::new(self.x - other.x, self.y - other.y)
+    fn clear(&mut self) {
+        for word in self.set.drain(..) {
+            self.bits[word] = 0;
+        }
     }
 }
 
-type Map = Vec<Vec<Tile>>;
-
-fn parse_input(input: &str) -> (Map, Point, Point) {
-    let mut start = Point::new(0, 0);
-    let mut direction = Point::new(0, 0);
-    let map = input
-        .lines()
-        .enumerate()
-        .map(|(row, line)| {
-            line.chars()
-                .enumerate()
-                .map(|(col, c)| match c {
-                    '^' | 'V' | '<' | '>' => {
-                        start = Point::new(col as i32, row as i32);
-                        direction = Point::from_direction(c);
-                        Tile::Empty
-                    }
-                    _ => Tile::from_char(c),
+/// Frames of the guard's path and of the obstructions that make it loop, shown with `--visualize`.
+#[cfg(feature = "visualize")]
+mod visualize {
+    use super::{Entry, Lab, States, DOWN, LEFT, RIGHT, UP};
+    use advent_of_code::grid::Point;
+    use advent_of_code::visual::{Cell, Color, Frame};
+
+    const WALL: Cell = Cell::new('#', Color::Grey);
+    const FLOOR: Cell = Cell::new('.', Color::Grey);
+
+    fn arrow(direction: usize) -> char {
+        match direction {
+            UP => '^',
+            RIGHT => '>',
+            DOWN => 'v',
+            LEFT => '<',
+            _ => unreachable!(),
+        }
+    }
+
+    impl Lab {
+        fn point(&self, tile: usize) -> Point {
+            Point::new((tile % self.width) as i32, (tile / self.width) as i32)
+        }
+
+        fn frame(&self) -> Frame {
+            let mut frame = Frame::new(self.width, self.height);
+            for (tile, &wall) in self.walls.iter().enumerate() {
+                frame.set(self.point(tile), if wall { WALL } else { FLOOR });
+            }
+            frame.set(
+                self.point(self.start),
+                Cell::new(arrow(self.direction), Color::Green),
+            );
+            frame
+        }
+
+        /// The path of the guard, drawn whenever it heads into a new direction.
+        pub(super) fn path_frames<'a>(
+            &'a self,
+            entries: &'a [Entry],
+        ) -> impl Iterator<Item = Frame> + 'a {
+            let mut path = self.frame();
+
+            entries.iter().enumerate().filter_map(move |(i, entry)| {
+                let point = self.point(entry.tile);
+                path.set(point, Cell::new(arrow(entry.direction), Color::Yellow));
+
+                let turns = entries
+                    .get(i + 1)
+                    .is_none_or(|next| next.direction != entry.direction);
+                turns.then(|| {
+                    let mut frame = path.clone();
+                    frame.set(point, Cell::new(arrow(entry.direction), Color::Red));
+                    frame.caption(format!("{} tiles visited", i + 2))
                 })
-                .collect()
-        })
-        .collect();
-    (map, start, direction)
+            })
+        }
+
+        /// The path of the guard with the obstructions that make it loop.
+        pub(super) fn obstructions_frame(&self, entries: &[Entry]) -> Frame {
+            let mut frame = self.frame();
+            let mut states = States::new(self.walls.len() * 4);
+            let mut loops = 0;
+
+            for entry in entries {
+                states.clear();
+                let cell = if self.loops_with_obstruction(entry, &mut states) {
+                    loops += 1;
+                    Cell::new('O', Color::Red)
+                } else {
+                    Cell::new('+', Color::Blue)
+                };
+                frame.set(self.point(entry.tile), cell);
+            }
+
+            frame.caption(format!("{loops} obstructions make the guard loop"))
+        }
+    }
 }
 
-fn walk_with_obstruction(
-    map: &Map,
-    position: &mut Point,
-    direction: &mut Point,
-    obstruction: Option<Point>,
-) -> Option<HashSet<Point>> {
-    let mut visited_positions = HashSet::new();
-    let mut visited_states = HashSet::new();
-
-    while let Some(&tile) = position.get_in_map(map) {
-        let effective_tile = if Some(*position) == obstruction {
-            Tile::Wall
-        } else {
-            tile
-        };
+/// The original implementation, kept as a reference for part two.
+mod reference {
+    use advent_of_code::hash::FastSet;
+    use std::ops::{Add, Sub};
 
-        match effective_tile {
-            Tile::Empty => {
-                visited_positions.insert(*position);
+    /// Tile in the map
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    pub(super) enum Tile {
+        Empty,
+        Wall,
+    }
 
-                // Check for cycle
-                let state = (*position, *direction);
-                if !visited_states.insert(state) {
-                    return None; // Cycle found
-                }
+    impl Tile {
+        fn from_char(c: char) -> Self {
+            match c {
+                '.' => Self::Empty,
+                '#' => Self::Wall,
+                _ => panic!("Invalid tile"),
             }
-            Tile::Wall => {
-                *position = *position - *direction;
-                direction.rotate_ccw();
+        }
+    }
+
+    #[derive(Debug, PartialEq, Hash, Eq, Clone, Copy)]
+    pub(super) struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    impl Point {
+        pub(super) fn new(x: i32, y: i32) -> Self {
+            Point { x, y }
+        }
+
+        /// Returns the tile at the given point from the map if valid.
+        fn get_in_map<'a>(&self, map: &'a Map) -> Option<&'a Tile> {
+            if self.x < 0 || self.y < 0 {
+                return None;
+            }
+            let (x, y) = (self.x as usize, self.y as usize);
+            map.get(y)?.get(x)
+        }
+
+        fn from_direction(c: char) -> Self {
+            match c {
+                '^' => Point::new(0, -1),
+                'v' => Point::new(0, 1),
+                '<' => Point::new(-1, 0),
+                '>' => Point::new(1, 0),
+                _ => panic!("Unexpected direction character: {}", c),
             }
         }
 
-        *position = *position + *direction;
+        /// Rotates the point direction 90 degrees counter-clockwise.
+        fn rotate_ccw(&mut self) {
+            let (old_x, old_y) = (self.x, self.y);
+            self.x = -old_y;
+            self.y = old_x;
+        }
     }
-    Some(visited_positions)
-}
 
-fn find_cyclic_paths(map: &Map, start: Point, direction: Point) -> usize {
-    let possible_obstructions =
-        walk_with_obstruction(map, &mut start.clone(), &mut direction.clone(), None)
-            .unwrap()
+    /// Implement addition for Point to simplify arithmetic
+    impl Add for Point {
+        type Output = Point;
+
+        fn add(self, other: Point) -> Point {
+            Point::new(self.x + other.x, self.y + other.y)
+        }
+    }
+
+    /// Implement subtraction for Point to simplify arithmetic
+    impl Sub for Point {
+        type Output = Point;
+
+        fn sub(self, other: Point) -> Point {
+            Point::new(self.x - other.x, self.y - other.y)
+        }
+    }
+
+    pub(super) type Map = Vec<Vec<Tile>>;
+
+    pub(super) fn parse_input(input: &str) -> (Map, Point, Point) {
+        let mut start = Point::new(0, 0);
+        let mut direction = Point::new(0, 0);
+        let map = input
+            .lines()
+            .enumerate()
+            .map(|(row, line)| {
+                line.chars()
+                    .enumerate()
+                    .map(|(col, c)| match c {
+                        '^' | 'V' | '<' | '>' => {
+                            start = Point::new(col as i32, row as i32);
+                            direction = Point::from_direction(c);
+                            Tile::Empty
+                        }
+                        _ => Tile::from_char(c),
+                    })
+                    .collect()
+            })
+            .collect();
+        (map, start, direction)
+    }
+
+    fn walk_with_obstruction(
+        map: &Map,
+        position: &mut Point,
+        direction: &mut Point,
+        obstruction: Option<Point>,
+    ) -> Option<FastSet<Point>> {
+        let mut visited_positions = FastSet::default();
+        let mut visited_states = FastSet::default();
+
+        while let Some(&tile) = position.get_in_map(map) {
+            let effective_tile = if Some(*position) == obstruction {
+                Tile::Wall
+            } else {
+                tile
+            };
+
+            match effective_tile {
+                Tile::Empty => {
+                    visited_positions.insert(*position);
+
+                    // Check for cycle
+                    let state = (*position, *direction);
+                    if !visited_states.insert(state) {
+                        return None; // Cycle found
+                    }
+                }
+                Tile::Wall => {
+                    *position = *position - *direction;
+                    direction.rotate_ccw();
+                }
+            }
+
+            *position = *position + *direction;
+        }
+        Some(visited_positions)
+    }
+
+    pub(super) fn find_cyclic_paths(map: &Map, start: Point, direction: Point) -> Option<usize> {
+        let possible_obstructions =
+            walk_with_obstruction(map, &mut start.clone(), &mut direction.clone(), None)?
+                .iter()
+                .filter(|&&point| point != start)
+                .cloned()
+                .collect::<Vec<_>>();
+
+        let cyclic_paths = possible_obstructions
             .iter()
-            .filter(|&&point| point != start)
-            .cloned()
-            .collect::<Vec<_>>();
-
-    possible_obstructions
-        .iter()
-        .map(|&obstruction| {
-            let mut new_start = start;
-            let mut new_direction = direction;
-            walk_with_obstruction(map, &mut new_start, &mut new_direction, Some(obstruction))
+            .map(|&obstruction| {
+                let mut new_start = start;
+                let mut new_direction = direction;
+                walk_with_obstruction(map, &mut new_start, &mut new_direction, Some(obstruction))
+            })
+            .filter(|visited| visited.is_none())
+            .count();
+
+        Some(cyclic_paths)
+    }
+}
+
+/// Generates a square map with a side of `10 * size` tiles, of which about one in ten is a wall.
+/// The guard starts facing up on a random empty tile.
+fn generate(seed: u64, size: usize) -> String {
+    let mut rng = Rng::new(seed);
+    let side = 10 * size;
+
+    let mut map: Vec<Vec<char>> = (0..side)
+        .map(|_| {
+            (0..side)
+                .map(|_| if rng.chance(0.1) { '#' } else { '.' })
+                .collect()
         })
-        .filter(|visited| visited.is_none())
-        .count()
+        .collect();
+
+    let (x, y) = (rng.range(0..side), rng.range(0..side));
+    map[y][x] = '^';
+
+    map.iter()
+        .map(|row| row.iter().collect::<String>() + "\n")
+        .collect()
 }
 
 #[cfg(test)]
@@ -176,10 +545,14 @@ mod tests {
     #[test]
     fn test_parse_input() {
         let input = &advent_of_code::template::read_file("examples", DAY);
-        let (map, start, direction) = parse_input(input);
+        let (map, start, direction) = reference::parse_input(input);
         assert_eq!(map.len(), 10);
-        assert_eq!(start, Point::new(4, 6));
-        assert_eq!(direction, Point::new(0, -1));
+        assert_eq!(start, reference::Point::new(4, 6));
+        assert_eq!(direction, reference::Point::new(0, -1));
+
+        let lab = Lab::parse(input);
+        assert_eq!((lab.width, lab.height), (10, 10));
+        assert_eq!((lab.start, lab.direction), (64, UP));
     }
 
     #[test]
@@ -193,4 +566,32 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(6));
     }
+
+    #[test]
+    fn test_part_two_reference() {
+        let result = part_two_reference(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Some(6));
+    }
+
+    #[test]
+    fn test_part_two_alternatives_agree() {
+        advent_of_code::template::differential::assert_agree(&SOLUTION, 20);
+    }
+
+    #[test]
+    fn test_jump_table() {
+        let lab = Lab::parse("..#\n...\n^..\n");
+        assert_eq!(lab.jumps[UP][8], Some(5));
+        assert_eq!(lab.jumps[UP][6], None);
+        assert_eq!(lab.jumps[RIGHT][0], Some(1));
+        assert_eq!(lab.jumps[LEFT][5], None);
+    }
+
+    #[test]
+    fn test_guard_stuck_in_loop() {
+        let input = ".#...\n....#\n.....\n#^...\n...#.\n";
+        assert_eq!(part_one(input), None);
+        assert_eq!(part_two(input), None);
+        assert_eq!(part_two_reference(input), None);
+    }
 }
@@ -1,10 +1,21 @@
-advent_of_code::solution!(7);
+advent_of_code::solution!(
+    7,
+    generator: generate,
+    alternatives: [2 => part_two_exhaustive],
+);
+use advent_of_code::combinatorics;
+use advent_of_code::expr::{self, Operator};
+use advent_of_code::rng::Rng;
+
+const OPERATORS: [Operator<u64>; 3] = [Operator::add(), Operator::mul(), Operator::concat()];
 
 pub fn part_one(input: &str) -> Option<u64> {
     let operations = parse_input(input);
     operations
         .iter()
-        .filter(|(target, values)| can_build_target(*target, values, false))
+        .filter(|(target, values)| {
+            expr::is_reachable(*target, values, &[Operator::add(), Operator::mul()])
+        })
         .map(|(target, _)| *target)
         .sum::<u64>()
         .into()
@@ -14,7 +25,32 @@ pub fn part_two(input: &str) -> Option<u64> {
     let operations = parse_input(input);
     operations
         .iter()
-        .filter(|(target, values)| can_build_target(*target, values, true))
+        .filter(|(target, values)| expr::is_reachable(*target, values, &OPERATORS))
+        .map(|(target, _)| *target)
+        .sum::<u64>()
+        .into()
+}
+
+/// Tries every choice of operators, evaluating forwards.
+pub fn part_two_exhaustive(input: &str) -> Option<u64> {
+    let operations = parse_input(input);
+    operations
+        .iter()
+        .filter(|(target, values)| {
+            let mut choices = combinatorics::cartesian_power(&OPERATORS, values.len() - 1);
+            while let Some(operators) = choices.next() {
+                let result = operators
+                    .iter()
+                    .zip(&values[1..])
+                    .try_fold(values[0], |left, (operator, &right)| {
+                        (operator.apply)(left, right)
+                    });
+                if result == Some(*target) {
+                    return true;
+                }
+            }
+            false
+        })
         .map(|(target, _)| *target)
         .sum::<u64>()
         .into()
@@ -39,60 +75,25 @@ fn parse_input(input: &str) -> Vec<(u64, Vec<u64>)> {
         .collect()
 }
 
-fn can_build_target(target: u64, values: &[u64], allow_concat: bool) -> bool {
-    match values {
-        [] => target == 0,
-        [.., value] => {
-            let without_last = &values[..values.len() - 1];
-
-            let divisible = target % *value == 0;
-            let subtractable = target >= *value;
-
-            // Try subtraction (equivalent to using '+', because we move left-to-right and
-            // interpret adding a negative after rearrangement. Here it directly represents trying target - value.)
-            let subtract_option = if subtractable {
-                can_build_target(target - *value, without_last, allow_concat)
-            } else {
-                false
-            };
-
-            // Try division (like a previous multiplication)
-            let divide_option = if divisible {
-                can_build_target(target / *value, without_last, allow_concat)
-            } else {
-                false
-            };
-
-            // Try concatenation if allowed
-            let concat_option = if allow_concat && can_concatenate(target, *value) {
-                let adjusted = remove_concatenatable(target, *value);
-                can_build_target(adjusted, without_last, allow_concat)
-            } else {
-                false
-            };
-
-            subtract_option || divide_option || concat_option
-        }
-    }
-}
-
-/// Check if `value` can be concatenated onto the end of `target`.
-fn can_concatenate(target: u64, value: u64) -> bool {
-    target.to_string().ends_with(&value.to_string())
-}
-
-/// Removes the concatenated portion (value) from the end of `target`.
-fn remove_concatenatable(target: u64, value: u64) -> u64 {
-    let target_str = target.to_string();
-    let value_str = value.to_string();
-
-    if target_str == value_str {
-        0
-    } else if let Some(prefix) = target_str.strip_suffix(&value_str) {
-        prefix.parse().expect("Prefix should be a number")
-    } else {
-        target
-    }
+/// Generates `10 * size` equations of two to seven operands between 1 and 99.
+/// Half of the targets are the result of random operators, the others are off by one.
+fn generate(seed: u64, size: usize) -> String {
+    let mut rng = Rng::new(seed);
+
+    (0..10 * size)
+        .map(|_| {
+            let len = rng.range(2..=7);
+            let values: Vec<u64> = (0..len).map(|_| rng.range(1..100)).collect();
+            let target = values[1..].iter().fold(values[0], |left, &right| {
+                let operator = rng.choose(&OPERATORS).unwrap();
+                (operator.apply)(left, right).unwrap_or(left)
+            });
+            let target = if rng.chance(0.5) { target } else { target + 1 };
+
+            let values: Vec<String> = values.iter().map(u64::to_string).collect();
+            format!("{target}: {}\n", values.join(" "))
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -106,6 +107,12 @@ mod tests {
         assert_eq!(parse_input(input), expected);
     }
 
+    #[test]
+    fn test_concatenation_witness() {
+        let expression = expr::solve(156, &[15, 6], &OPERATORS).unwrap();
+        assert_eq!(expression.to_string(), "15 || 6");
+    }
+
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
@@ -117,4 +124,15 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(11387));
     }
+
+    #[test]
+    fn test_part_two_exhaustive() {
+        let result = part_two_exhaustive(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Some(11387));
+    }
+
+    #[test]
+    fn test_part_two_alternatives_agree() {
+        advent_of_code::template::differential::assert_agree(&SOLUTION, 20);
+    }
 }
@@ -1,5 +1,7 @@
 advent_of_code::solution!(8);
-use std::collections::{HashMap, HashSet};
+use advent_of_code::combinatorics;
+use advent_of_code::grid::{Bounds, Point, SparseGrid};
+use advent_of_code::hash::FastMap;
 
 pub fn part_one(input: &str) -> Option<u32> {
     Some(solve(input, false))
@@ -13,76 +15,49 @@ fn solve(input: &str, multi_mode: bool) -> u32 {
     parse_input(input, multi_mode).len() as u32
 }
 
-fn iter_char_locations(input: &str) -> impl Iterator<Item = (char, i32, i32)> + '_ {
-    input.lines().enumerate().flat_map(|(y, line)| {
-        line.chars()
-            .enumerate()
-            .map(move |(x, c)| (c, x as i32, y as i32))
-    })
-}
-
-type Antennas = HashMap<char, HashSet<(i32, i32)>>;
-type Antinodes = HashSet<(i32, i32)>;
-
-fn within_bounds(x: i32, y: i32, max_x: i32, max_y: i32) -> bool {
-    x >= 0 && x < max_x && y >= 0 && y < max_y
-}
+type Antennas = FastMap<char, Vec<Point>>;
+type Antinodes = SparseGrid<()>;
 
 fn insert_antinode(
     antinodes: &mut Antinodes,
-    start: (i32, i32),
-    dir: (i32, i32),
-    max: (i32, i32),
+    start: Point,
+    dir: Point,
+    map: Bounds,
     multi_mode: bool,
 ) {
-    let (max_x, max_y) = max;
-    let (mut x, mut y) = start;
-    let (dx, dy) = dir;
-
     if multi_mode {
-        while within_bounds(x, y, max_x, max_y) {
-            antinodes.insert((x, y));
-            x += dx;
-            y += dy;
-        }
-    } else {
-        let (nx, ny) = (x + dx, y + dy);
-        if within_bounds(nx, ny, max_x, max_y) {
-            antinodes.insert((nx, ny));
+        let mut point = start;
+        while map.contains(point) {
+            antinodes.insert(point, ());
+            point += dir;
         }
+    } else if map.contains(start + dir) {
+        antinodes.insert(start + dir, ());
     }
 }
 
 fn parse_input(input: &str, multi_mode: bool) -> Antinodes {
-    let rows = input.lines().count() as i32;
-    let cols = input.lines().next().unwrap_or("").chars().count() as i32;
-    let max = (cols, rows);
-
-    let mut antenna_locations: Antennas = HashMap::new();
-    let mut antinodes: Antinodes = HashSet::new();
+    let rows = input.lines().count();
+    let cols = input.lines().next().unwrap_or("").chars().count();
+    if rows == 0 || cols == 0 {
+        return Antinodes::new();
+    }
+    let map = Bounds::from_size(cols, rows);
 
-    for (c, x, y) in iter_char_locations(input) {
-        if c == '.' {
-            continue;
-        }
+    let antennas = SparseGrid::parse(input, |c| (c != '.').then_some(c));
+    let mut antenna_locations: Antennas = FastMap::default();
+    let mut antinodes = Antinodes::new();
 
-        if let Some(coordinates) = antenna_locations.get(&c) {
-            for &(other_x, other_y) in coordinates {
-                let dx = x - other_x;
-                let dy = y - other_y;
+    for (point, &c) in antennas.iter() {
+        antenna_locations.entry(c).or_default().push(point);
+    }
 
-                insert_antinode(&mut antinodes, (x, y), (dx, dy), max, multi_mode);
-                insert_antinode(
-                    &mut antinodes,
-                    (other_x, other_y),
-                    (-dx, -dy),
-                    max,
-                    multi_mode,
-                );
-            }
+    for points in antenna_locations.values() {
+        for (&a, &b) in combinatorics::pairs(points) {
+            let dir = b - a;
+            insert_antinode(&mut antinodes, b, dir, map, multi_mode);
+            insert_antinode(&mut antinodes, a, -dir, map, multi_mode);
         }
-
-        antenna_locations.entry(c).or_default().insert((x, y));
     }
 
     antinodes
@@ -92,13 +67,6 @@ fn parse_input(input: &str, multi_mode: bool) -> Antinodes {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_iter_char_locations() {
-        let input = &advent_of_code::template::read_file("examples", DAY);
-        let total = iter_char_locations(input).count();
-        assert_eq!(total, 144)
-    }
-
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
@@ -110,4 +78,31 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(34));
     }
+
+    #[test]
+    fn test_empty_map() {
+        assert_eq!(part_one(""), Some(0));
+        assert_eq!(part_two("\n"), Some(0));
+    }
+
+    #[test]
+    fn test_render_antinodes() {
+        let input = &advent_of_code::template::read_file("examples", DAY);
+        let antinodes = parse_input(input, false);
+        let expected = "\
+......#....#
+...#........
+....#.....#.
+..#.........
+.........#..
+.#....#.....
+...#........
+#......#....
+............
+............
+..........#.
+..........#.";
+        let map = Bounds::from_size(12, 12);
+        assert_eq!(antinodes.render_within(map, '.', |_| '#'), expected);
+    }
 }
@@ -0,0 +1,323 @@
+//! Iterators over selections of items from a slice.
+//!
+//! [`pairs`] is a regular iterator. The other selections have a length only known at runtime, so they
+//! lend each selection as a slice of a buffer that is reused, and don't allocate per selection.
+//! They are consumed with `while let`:
+//!
+//! ```
+//! use advent_of_code::combinatorics;
+//!
+//! let mut operators = combinatorics::cartesian_power(&['+', '*'], 2);
+//! let mut all = vec![];
+//! while let Some(choice) = operators.next() {
+//!     all.push(choice.iter().copied().collect::<String>());
+//! }
+//! assert_eq!(all, ["++", "+*", "*+", "**"]);
+//! ```
+//!
+//! All selections are produced in lexicographic order of the positions of their items.
+
+/// All unordered pairs of distinct positions, `(items[i], items[j])` with `i < j`.
+pub fn pairs<T>(items: &[T]) -> Pairs<'_, T> {
+    Pairs { items, i: 0, j: 1 }
+}
+
+/// All sequences of `n` choices, with repetition. There are `choices.len()^n` of them.
+pub fn cartesian_power<T>(choices: &[T], n: usize) -> CartesianPower<'_, T> {
+    CartesianPower {
+        selection: Selection::new(choices, vec![0; n], !choices.is_empty() || n == 0),
+    }
+}
+
+/// All ordered selections of `k` items with repetition, which are the [`cartesian_power`].
+pub fn permutations_with_repetition<T>(items: &[T], k: usize) -> CartesianPower<'_, T> {
+    cartesian_power(items, k)
+}
+
+/// All ordered selections of `k` items at distinct positions.
+pub fn permutations<T>(items: &[T], k: usize) -> Permutations<'_, T> {
+    let mut used = vec![false; items.len()];
+    used.iter_mut().take(k).for_each(|used| *used = true);
+    Permutations {
+        selection: Selection::new(items, (0..k).collect(), k <= items.len()),
+        used,
+    }
+}
+
+/// All unordered selections of `k` items at distinct positions.
+pub fn combinations<T>(items: &[T], k: usize) -> Combinations<'_, T> {
+    Combinations {
+        selection: Selection::new(items, (0..k).collect(), k <= items.len()),
+        repetition: false,
+    }
+}
+
+/// All unordered selections of `k` items, where a position can be selected more than once.
+pub fn combinations_with_repetition<T>(items: &[T], k: usize) -> Combinations<'_, T> {
+    Combinations {
+        selection: Selection::new(items, vec![0; k], !items.is_empty() || k == 0),
+        repetition: true,
+    }
+}
+
+/// Iterator over unordered pairs, see [`pairs`].
+#[derive(Debug, Clone)]
+pub struct Pairs<'a, T> {
+    items: &'a [T],
+    i: usize,
+    j: usize,
+}
+
+impl<'a, T> Iterator for Pairs<'a, T> {
+    type Item = (&'a T, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.j >= self.items.len() {
+            return None;
+        }
+        let pair = (&self.items[self.i], &self.items[self.j]);
+
+        self.j += 1;
+        if self.j == self.items.len() {
+            self.i += 1;
+            self.j = self.i + 1;
+        }
+        Some(pair)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.items.len();
+        // NOTE: the pairs of all later first positions, plus the rest of the current one.
+        let later = n.saturating_sub(self.i + 1) * n.saturating_sub(self.i + 2) / 2;
+        let len = later + n.saturating_sub(self.j);
+        (len, Some(len))
+    }
+}
+
+impl<T> ExactSizeIterator for Pairs<'_, T> {}
+
+/// The positions of the current selection and the items at those positions.
+#[derive(Debug, Clone)]
+struct Selection<'a, T> {
+    items: &'a [T],
+    indices: Vec<usize>,
+    buffer: Vec<&'a T>,
+    started: bool,
+    done: bool,
+}
+
+impl<'a, T> Selection<'a, T> {
+    fn new(items: &'a [T], indices: Vec<usize>, any: bool) -> Self {
+        Self {
+            items,
+            buffer: Vec::with_capacity(indices.len()),
+            indices,
+            started: false,
+            done: !any,
+        }
+    }
+
+    /// Move to the next selection with `advance` unless this is the first one, and lend it.
+    fn next(&mut self, advance: impl FnOnce(&mut [usize]) -> bool) -> Option<&[&'a T]> {
+        if self.done {
+            return None;
+        }
+        if self.started && !advance(&mut self.indices) {
+            self.done = true;
+            return None;
+        }
+        self.started = true;
+
+        self.buffer.clear();
+        self.buffer
+            .extend(self.indices.iter().map(|&i| &self.items[i]));
+        Some(&self.buffer)
+    }
+}
+
+/// Lending iterator over sequences of choices, see [`cartesian_power`].
+#[derive(Debug, Clone)]
+pub struct CartesianPower<'a, T> {
+    selection: Selection<'a, T>,
+}
+
+impl<'a, T> CartesianPower<'a, T> {
+    /// The next sequence, or `None` once all were produced.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[&'a T]> {
+        let choices = self.selection.items.len();
+
+        self.selection.next(|indices| {
+            // NOTE: count like an odometer, the last position changes fastest.
+            for index in indices.iter_mut().rev() {
+                *index += 1;
+                if *index < choices {
+                    return true;
+                }
+                *index = 0;
+            }
+            false
+        })
+    }
+}
+
+/// Lending iterator over ordered selections, see [`permutations`].
+#[derive(Debug, Clone)]
+pub struct Permutations<'a, T> {
+    selection: Selection<'a, T>,
+    /// Whether each position is part of the current selection.
+    used: Vec<bool>,
+}
+
+impl<'a, T> Permutations<'a, T> {
+    /// The next selection, or `None` once all were produced.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[&'a T]> {
+        let used = &mut self.used;
+
+        self.selection.next(|indices| {
+            // NOTE: move the last slot that can take a larger unused position, then fill the slots
+            // after it with the smallest unused positions.
+            for slot in (0..indices.len()).rev() {
+                used[indices[slot]] = false;
+                let Some(next) = (indices[slot] + 1..used.len()).find(|&i| !used[i]) else {
+                    continue;
+                };
+                indices[slot] = next;
+                used[next] = true;
+
+                let mut free = (0..used.len()).filter(|&i| !used[i]);
+                for index in &mut indices[slot + 1..] {
+                    *index = free.next().expect("enough positions are unused");
+                }
+                for &index in &indices[slot + 1..] {
+                    used[index] = true;
+                }
+                return true;
+            }
+            false
+        })
+    }
+}
+
+/// Lending iterator over unordered selections, see [`combinations`] and [`combinations_with_repetition`].
+#[derive(Debug, Clone)]
+pub struct Combinations<'a, T> {
+    selection: Selection<'a, T>,
+    repetition: bool,
+}
+
+impl<'a, T> Combinations<'a, T> {
+    /// The next selection, or `None` once all were produced.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[&'a T]> {
+        let n = self.selection.items.len();
+        let repetition = self.repetition;
+
+        self.selection.next(|indices| {
+            // NOTE: positions are increasing (or non-decreasing with repetition), so the slot at `i`
+            // can hold at most the position that still leaves room for the slots after it.
+            let k = indices.len();
+            let max = |slot: usize| if repetition { n - 1 } else { n - k + slot };
+
+            let Some(slot) = (0..k).rev().find(|&slot| indices[slot] < max(slot)) else {
+                return false;
+            };
+            indices[slot] += 1;
+            for i in slot + 1..k {
+                indices[i] = indices[i - 1] + usize::from(!repetition);
+            }
+            true
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{
+        cartesian_power, combinations, combinations_with_repetition, pairs, permutations,
+        permutations_with_repetition,
+    };
+
+    /// Collects the selections of a lending iterator as strings.
+    macro_rules! collect {
+        ($selections:expr) => {{
+            let mut selections = $selections;
+            let mut all = vec![];
+            while let Some(selection) = selections.next() {
+                all.push(selection.iter().copied().collect::<String>());
+            }
+            all
+        }};
+    }
+
+    const ITEMS: [char; 4] = ['a', 'b', 'c', 'd'];
+
+    #[test]
+    fn iterates_pairs() {
+        let all: Vec<_> = pairs(&[1, 2, 3]).collect();
+        assert_eq!(all, [(&1, &2), (&1, &3), (&2, &3)]);
+
+        let mut iter = pairs(&ITEMS);
+        assert_eq!(iter.len(), 6);
+        iter.next();
+        iter.next();
+        iter.next();
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.count(), 3);
+
+        assert_eq!(pairs(&[1]).count(), 0);
+        assert_eq!(pairs::<u8>(&[]).len(), 0);
+    }
+
+    #[test]
+    fn iterates_cartesian_powers() {
+        assert_eq!(
+            collect!(cartesian_power(&['x', 'y'], 3)),
+            ["xxx", "xxy", "xyx", "xyy", "yxx", "yxy", "yyx", "yyy"]
+        );
+        assert_eq!(collect!(cartesian_power(&ITEMS, 0)), [""]);
+        assert_eq!(collect!(cartesian_power::<char>(&[], 2)).len(), 0);
+        assert_eq!(collect!(permutations_with_repetition(&ITEMS, 3)).len(), 64);
+    }
+
+    #[test]
+    fn iterates_permutations() {
+        assert_eq!(
+            collect!(permutations(&ITEMS[..3], 3)),
+            ["abc", "acb", "bac", "bca", "cab", "cba"]
+        );
+        assert_eq!(
+            collect!(permutations(&ITEMS, 2)),
+            ["ab", "ac", "ad", "ba", "bc", "bd", "ca", "cb", "cd", "da", "db", "dc"]
+        );
+        assert_eq!(collect!(permutations(&ITEMS, 0)), [""]);
+        assert_eq!(collect!(permutations(&ITEMS, 5)).len(), 0);
+        assert_eq!(collect!(permutations(&['a'; 5], 5)).len(), 120);
+    }
+
+    #[test]
+    fn iterates_combinations() {
+        assert_eq!(
+            collect!(combinations(&ITEMS, 2)),
+            ["ab", "ac", "ad", "bc", "bd", "cd"]
+        );
+        assert_eq!(collect!(combinations(&ITEMS, 4)), ["abcd"]);
+        assert_eq!(collect!(combinations(&ITEMS, 0)), [""]);
+        assert_eq!(collect!(combinations(&ITEMS, 5)).len(), 0);
+        assert_eq!(collect!(combinations(&['a'; 10], 3)).len(), 120);
+
+        assert_eq!(
+            collect!(combinations_with_repetition(&ITEMS[..3], 2)),
+            ["aa", "ab", "ac", "bb", "bc", "cc"]
+        );
+        assert_eq!(collect!(combinations_with_repetition(&ITEMS, 3)).len(), 20);
+        assert_eq!(
+            collect!(combinations_with_repetition::<char>(&[], 1)).len(),
+            0
+        );
+        assert_eq!(collect!(combinations_with_repetition::<char>(&[], 0)), [""]);
+    }
+}
@@ -0,0 +1,219 @@
+//! A multiset that counts how often each item occurs.
+//!
+//! ```
+//! use advent_of_code::counter::Counter;
+//!
+//! let counter: Counter<char> = "hello".chars().collect();
+//! assert_eq!(counter.get(&'l'), 2);
+//! assert_eq!(counter.most_common()[0], (&'l', 2));
+//! ```
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::{Add, BitAnd, BitOr, Sub};
+
+/// Counts of items, items that were never added have a count of zero.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counter<T: Eq + Hash> {
+    counts: HashMap<T, usize>,
+}
+
+impl<T: Eq + Hash> Default for Counter<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Eq + Hash> Counter<T> {
+    pub fn new() -> Self {
+        Self {
+            counts: HashMap::new(),
+        }
+    }
+
+    /// The number of times `item` was added.
+    pub fn get(&self, item: &T) -> usize {
+        self.counts.get(item).copied().unwrap_or_default()
+    }
+
+    /// Add `item` once.
+    pub fn add(&mut self, item: T) {
+        self.add_n(item, 1);
+    }
+
+    /// Add `item` `n` times.
+    pub fn add_n(&mut self, item: T, n: usize) {
+        if n > 0 {
+            *self.counts.entry(item).or_default() += n;
+        }
+    }
+
+    /// Remove `item` up to `n` times. Returns the number of times it was removed.
+    pub fn remove_n(&mut self, item: &T, n: usize) -> usize {
+        let Some(count) = self.counts.get_mut(item) else {
+            return 0;
+        };
+
+        let removed = n.min(*count);
+        *count -= removed;
+        if *count == 0 {
+            self.counts.remove(item);
+        }
+        removed
+    }
+
+    /// The number of distinct items.
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// The sum of all counts.
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    /// Distinct items with their counts, in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (&T, usize)> {
+        self.counts.iter().map(|(item, &count)| (item, count))
+    }
+
+    /// Items with their counts, most common first. Ties are in arbitrary order.
+    pub fn most_common(&self) -> Vec<(&T, usize)> {
+        let mut items: Vec<_> = self.iter().collect();
+        items.sort_unstable_by_key(|&(_, count)| std::cmp::Reverse(count));
+        items
+    }
+}
+
+impl<T: Eq + Hash> FromIterator<T> for Counter<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut counter = Self::new();
+        counter.extend(iter);
+        counter
+    }
+}
+
+impl<T: Eq + Hash> Extend<T> for Counter<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.add(item);
+        }
+    }
+}
+
+impl<T: Eq + Hash> IntoIterator for Counter<T> {
+    type Item = (T, usize);
+    type IntoIter = std::collections::hash_map::IntoIter<T, usize>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.counts.into_iter()
+    }
+}
+
+/// Sum of the counts.
+impl<T: Eq + Hash> Add for Counter<T> {
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self {
+        for (item, count) in rhs {
+            self.add_n(item, count);
+        }
+        self
+    }
+}
+
+/// Difference of the counts, items whose count drops to zero or below are removed.
+impl<T: Eq + Hash> Sub for Counter<T> {
+    type Output = Self;
+
+    fn sub(mut self, rhs: Self) -> Self {
+        for (item, count) in &rhs.counts {
+            self.remove_n(item, *count);
+        }
+        self
+    }
+}
+
+/// Intersection, the minimum of the counts.
+impl<T: Eq + Hash> BitAnd for Counter<T> {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        let counts = self
+            .counts
+            .into_iter()
+            .filter_map(|(item, count)| {
+                let count = count.min(rhs.get(&item));
+                (count > 0).then_some((item, count))
+            })
+            .collect();
+        Self { counts }
+    }
+}
+
+/// Union, the maximum of the counts.
+impl<T: Eq + Hash> BitOr for Counter<T> {
+    type Output = Self;
+
+    fn bitor(mut self, rhs: Self) -> Self {
+        for (item, count) in rhs {
+            let entry = self.counts.entry(item).or_default();
+            *entry = (*entry).max(count);
+        }
+        self
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Counter;
+
+    fn counts(counter: &Counter<char>) -> Vec<(char, usize)> {
+        let mut counts: Vec<_> = counter.iter().map(|(&c, n)| (c, n)).collect();
+        counts.sort_unstable();
+        counts
+    }
+
+    #[test]
+    fn counts_items() {
+        let mut counter: Counter<char> = "abracadabra".chars().collect();
+        assert_eq!(counter.get(&'a'), 5);
+        assert_eq!(counter.get(&'z'), 0);
+        assert_eq!(counter.len(), 5);
+        assert_eq!(counter.total(), 11);
+
+        counter.extend("zz".chars());
+        assert_eq!(counter.get(&'z'), 2);
+
+        assert_eq!(counter.remove_n(&'z', 5), 2);
+        assert_eq!(counter.remove_n(&'z', 1), 0);
+        assert_eq!(counter.len(), 5);
+    }
+
+    #[test]
+    fn orders_by_count() {
+        let counter: Counter<char> = "abracadabra".chars().collect();
+        let most_common = counter.most_common();
+        assert_eq!(most_common[0], (&'a', 5));
+        assert!(most_common.windows(2).all(|w| w[0].1 >= w[1].1));
+    }
+
+    #[test]
+    fn combines_counters() {
+        let a: Counter<char> = "aab".chars().collect();
+        let b: Counter<char> = "abbc".chars().collect();
+
+        assert_eq!(
+            counts(&(a.clone() + b.clone())),
+            vec![('a', 3), ('b', 3), ('c', 1)]
+        );
+        assert_eq!(counts(&(a.clone() - b.clone())), vec![('a', 1)]);
+        assert_eq!(counts(&(a.clone() & b.clone())), vec![('a', 1), ('b', 1)]);
+        assert_eq!(counts(&(a | b)), vec![('a', 2), ('b', 2), ('c', 1)]);
+    }
+}
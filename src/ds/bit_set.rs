@@ -0,0 +1,153 @@
+/// A set of the integers `0..capacity`, stored as bits.
+///
+/// ```
+/// use advent_of_code::ds::BitSet;
+///
+/// let mut set = BitSet::new(100);
+/// assert!(set.insert(42));
+/// assert!(!set.insert(42));
+/// assert!(set.contains(42));
+/// assert_eq!(set.iter().collect::<Vec<_>>(), vec![42]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitSet {
+    words: Vec<u64>,
+    capacity: usize,
+}
+
+impl BitSet {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            words: vec![0; capacity.div_ceil(64)],
+            capacity,
+        }
+    }
+
+    /// The number of integers the set can hold.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Insert `value`, returns `false` if it was already present.
+    ///
+    /// # Panics
+    /// If `value` is not below the capacity.
+    pub fn insert(&mut self, value: usize) -> bool {
+        let (word, bit) = self.locate(value);
+        let absent = self.words[word] & bit == 0;
+        self.words[word] |= bit;
+        absent
+    }
+
+    /// Remove `value`, returns `false` if it was not present.
+    pub fn remove(&mut self, value: usize) -> bool {
+        let (word, bit) = self.locate(value);
+        let present = self.words[word] & bit != 0;
+        self.words[word] &= !bit;
+        present
+    }
+
+    pub fn contains(&self, value: usize) -> bool {
+        value < self.capacity && self.words[value / 64] & (1 << (value % 64)) != 0
+    }
+
+    /// The number of integers in the set.
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    /// The integers in the set, in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                (word != 0).then(|| {
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    i * 64 + bit
+                })
+            })
+        })
+    }
+
+    /// Add all integers of `other`, which must have the same capacity.
+    pub fn union_with(&mut self, other: &Self) {
+        assert_eq!(self.capacity, other.capacity, "capacities differ");
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a |= b;
+        }
+    }
+
+    /// Keep only the integers that are in `other` as well, which must have the same capacity.
+    pub fn intersect_with(&mut self, other: &Self) {
+        assert_eq!(self.capacity, other.capacity, "capacities differ");
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a &= b;
+        }
+    }
+
+    fn locate(&self, value: usize) -> (usize, u64) {
+        assert!(
+            value < self.capacity,
+            "{value} is out of bounds for a bit set of capacity {}",
+            self.capacity
+        );
+        (value / 64, 1 << (value % 64))
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::BitSet;
+
+    #[test]
+    fn inserts_and_removes() {
+        let mut set = BitSet::new(130);
+        assert!(set.is_empty());
+        for value in [0, 63, 64, 129] {
+            assert!(set.insert(value));
+        }
+        assert!(!set.insert(64));
+        assert_eq!(set.len(), 4);
+        assert!(set.contains(129));
+        assert!(!set.contains(130));
+
+        assert!(set.remove(63));
+        assert!(!set.remove(63));
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![0, 64, 129]);
+
+        set.clear();
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn combines_sets() {
+        let mut a = BitSet::new(70);
+        let mut b = BitSet::new(70);
+        [1, 2, 65].iter().for_each(|&v| _ = a.insert(v));
+        [2, 3, 65].iter().for_each(|&v| _ = b.insert(v));
+
+        let mut union = a.clone();
+        union.union_with(&b);
+        assert_eq!(union.iter().collect::<Vec<_>>(), vec![1, 2, 3, 65]);
+
+        a.intersect_with(&b);
+        assert_eq!(a.iter().collect::<Vec<_>>(), vec![2, 65]);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn panics_out_of_bounds() {
+        BitSet::new(10).insert(10);
+    }
+}
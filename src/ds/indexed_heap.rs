@@ -0,0 +1,199 @@
+/// A min-heap of the items `0..n` with a key each, supporting decrease-key as needed by Dijkstra.
+///
+/// ```
+/// use advent_of_code::ds::IndexedMinHeap;
+///
+/// let mut heap = IndexedMinHeap::new(3);
+/// heap.push(0, 5);
+/// heap.push(1, 3);
+/// heap.push(0, 1); // decreases the key of item 0
+/// assert_eq!(heap.pop(), Some((0, 1)));
+/// assert_eq!(heap.pop(), Some((1, 3)));
+/// assert_eq!(heap.pop(), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct IndexedMinHeap<K> {
+    /// Items in heap order.
+    heap: Vec<usize>,
+    /// Position of each item in `heap`, if it is queued.
+    positions: Vec<Option<usize>>,
+    keys: Vec<Option<K>>,
+}
+
+impl<K: Ord> IndexedMinHeap<K> {
+    /// An empty heap for the items `0..n`.
+    pub fn new(n: usize) -> Self {
+        Self {
+            heap: Vec::with_capacity(n),
+            positions: vec![None; n],
+            keys: (0..n).map(|_| None).collect(),
+        }
+    }
+
+    /// The number of queued items.
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Returns `true` if `item` is queued.
+    pub fn contains(&self, item: usize) -> bool {
+        self.positions[item].is_some()
+    }
+
+    /// The key of a queued item.
+    pub fn key(&self, item: usize) -> Option<&K> {
+        self.positions[item].and(self.keys[item].as_ref())
+    }
+
+    /// Queue `item` with `key`, or decrease its key if it is queued with a larger key.
+    /// Returns `false` if the item was already queued with a key that is not larger.
+    pub fn push(&mut self, item: usize, key: K) -> bool {
+        match self.positions[item] {
+            Some(position) => {
+                if self.keys[item]
+                    .as_ref()
+                    .is_some_and(|current| *current <= key)
+                {
+                    return false;
+                }
+                self.keys[item] = Some(key);
+                self.sift_up(position);
+            }
+            None => {
+                self.keys[item] = Some(key);
+                self.positions[item] = Some(self.heap.len());
+                self.heap.push(item);
+                self.sift_up(self.heap.len() - 1);
+            }
+        }
+        true
+    }
+
+    /// The item with the smallest key.
+    pub fn peek(&self) -> Option<(usize, &K)> {
+        let &item = self.heap.first()?;
+        self.keys[item].as_ref().map(|key| (item, key))
+    }
+
+    /// Remove the item with the smallest key.
+    pub fn pop(&mut self) -> Option<(usize, K)> {
+        let last = self.heap.len().checked_sub(1)?;
+        self.swap(0, last);
+        let item = self.heap.pop()?;
+        self.positions[item] = None;
+        if !self.heap.is_empty() {
+            self.sift_down(0);
+        }
+        self.keys[item].take().map(|key| (item, key))
+    }
+
+    fn less(&self, a: usize, b: usize) -> bool {
+        self.keys[self.heap[a]] < self.keys[self.heap[b]]
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        self.positions[self.heap[a]] = Some(a);
+        self.positions[self.heap[b]] = Some(b);
+    }
+
+    fn sift_up(&mut self, mut position: usize) {
+        while position > 0 {
+            let parent = (position - 1) / 2;
+            if !self.less(position, parent) {
+                break;
+            }
+            self.swap(position, parent);
+            position = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut position: usize) {
+        loop {
+            let (left, right) = (2 * position + 1, 2 * position + 2);
+            let mut smallest = position;
+            if left < self.heap.len() && self.less(left, smallest) {
+                smallest = left;
+            }
+            if right < self.heap.len() && self.less(right, smallest) {
+                smallest = right;
+            }
+            if smallest == position {
+                break;
+            }
+            self.swap(position, smallest);
+            position = smallest;
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::IndexedMinHeap;
+    use crate::rng::Rng;
+
+    #[test]
+    fn pops_in_key_order() {
+        let mut rng = Rng::new(43);
+        let keys: Vec<u64> = (0..200).map(|_| rng.range(0..1000)).collect();
+
+        let mut heap = IndexedMinHeap::new(keys.len());
+        for (item, &key) in keys.iter().enumerate() {
+            heap.push(item, key);
+        }
+        assert_eq!(heap.len(), keys.len());
+
+        let popped: Vec<u64> = std::iter::from_fn(|| heap.pop())
+            .map(|(_, key)| key)
+            .collect();
+        let mut sorted = keys.clone();
+        sorted.sort_unstable();
+        assert_eq!(popped, sorted);
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn decreases_keys() {
+        let mut heap = IndexedMinHeap::new(3);
+        heap.push(0, 10);
+        heap.push(1, 20);
+        heap.push(2, 30);
+
+        assert!(heap.push(2, 5));
+        assert!(!heap.push(1, 25));
+        assert_eq!(heap.key(1), Some(&20));
+        assert_eq!(heap.peek(), Some((2, &5)));
+
+        assert_eq!(heap.pop(), Some((2, 5)));
+        assert!(!heap.contains(2));
+        assert_eq!(heap.key(2), None);
+        assert!(heap.push(2, 1));
+        assert_eq!(heap.pop(), Some((2, 1)));
+    }
+
+    #[test]
+    fn runs_dijkstra() {
+        // NOTE: edges (from, to, weight) of a small graph, the shortest path 0 -> 3 is 0 -> 2 -> 1 -> 3.
+        let edges = [(0, 1, 4), (0, 2, 1), (2, 1, 2), (1, 3, 1), (2, 3, 5)];
+        let mut distances = [u32::MAX; 4];
+        let mut heap = IndexedMinHeap::new(4);
+        heap.push(0, 0);
+
+        while let Some((node, distance)) = heap.pop() {
+            distances[node] = distance;
+            for &(_, to, weight) in edges.iter().filter(|(from, ..)| *from == node) {
+                if distances[to] == u32::MAX {
+                    heap.push(to, distance + weight);
+                }
+            }
+        }
+
+        assert_eq!(distances, [0, 3, 1, 4]);
+    }
+}
@@ -0,0 +1,195 @@
+use std::ops::Range;
+
+use crate::math::Integer;
+
+/// A set of integers stored as sorted, disjoint half-open ranges.
+/// Adjacent and overlapping ranges are merged.
+///
+/// ```
+/// use advent_of_code::ds::IntervalSet;
+///
+/// let mut set = IntervalSet::new();
+/// set.insert(0..10);
+/// set.insert(5..15);
+/// set.remove(3..4);
+/// assert_eq!(set.ranges(), &[0..3, 4..15]);
+/// assert_eq!(set.len(), 14);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T: Integer> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self { ranges: vec![] }
+    }
+
+    /// The disjoint ranges of the set, in ascending order.
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    /// The number of integers in the set.
+    pub fn len(&self) -> T {
+        self.ranges
+            .iter()
+            .fold(T::ZERO, |len, range| len + (range.end - range.start))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let index = self.ranges.partition_point(|range| range.end <= value);
+        self.ranges
+            .get(index)
+            .is_some_and(|range| range.start <= value)
+    }
+
+    /// Add the integers of `range`.
+    pub fn insert(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+
+        // NOTE: ranges touching the new one are merged into it.
+        let first = self.ranges.partition_point(|r| r.end < range.start);
+        let last = self.ranges.partition_point(|r| r.start <= range.end);
+
+        let merged = match self.ranges.get(first..last) {
+            Some([first, .., last]) | Some([first @ last]) => {
+                first.start.min(range.start)..last.end.max(range.end)
+            }
+            _ => range,
+        };
+        self.ranges.splice(first..last, [merged]);
+    }
+
+    /// Remove the integers of `range`.
+    pub fn remove(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+
+        let first = self.ranges.partition_point(|r| r.end <= range.start);
+        let last = self.ranges.partition_point(|r| r.start < range.end);
+        if first >= last {
+            return;
+        }
+
+        let left = self.ranges[first].start..range.start;
+        let right = range.end..self.ranges[last - 1].end;
+        let kept = [left, right].into_iter().filter(|r| !r.is_empty());
+        self.ranges.splice(first..last, kept);
+    }
+
+    /// The integers in either set.
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        for range in &other.ranges {
+            union.insert(range.clone());
+        }
+        union
+    }
+
+    /// The integers in both sets.
+    pub fn intersection(&self, other: &Self) -> Self {
+        let (mut i, mut j) = (0, 0);
+        let mut ranges = vec![];
+
+        while let (Some(a), Some(b)) = (self.ranges.get(i), other.ranges.get(j)) {
+            let overlap = a.start.max(b.start)..a.end.min(b.end);
+            if !overlap.is_empty() {
+                ranges.push(overlap);
+            }
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        Self { ranges }
+    }
+}
+
+impl<T: Integer> FromIterator<Range<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for range in iter {
+            set.insert(range);
+        }
+        set
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::IntervalSet;
+    use crate::rng::Rng;
+
+    #[test]
+    fn merges_ranges() {
+        let mut set: IntervalSet<i64> = [10..20, 30..40, 0..5].into_iter().collect();
+        assert_eq!(set.ranges(), &[0..5, 10..20, 30..40]);
+
+        set.insert(5..10);
+        assert_eq!(set.ranges(), &[0..20, 30..40]);
+
+        set.insert(15..35);
+        assert_eq!(set.ranges(), &[0..40]);
+        assert_eq!(set.len(), 40);
+
+        set.insert(50..50);
+        assert_eq!(set.ranges(), &[0..40]);
+    }
+
+    #[test]
+    fn removes_ranges() {
+        let mut set: IntervalSet<i64> = [0..10, 20..30].into_iter().collect();
+        set.remove(5..25);
+        assert_eq!(set.ranges(), &[0..5, 25..30]);
+
+        set.remove(0..5);
+        assert_eq!(set.ranges(), &[25..30]);
+
+        set.remove(-10..0);
+        assert_eq!(set.ranges(), &[25..30]);
+        assert!(set.contains(25));
+        assert!(!set.contains(30));
+    }
+
+    #[test]
+    fn combines_sets() {
+        let a: IntervalSet<u32> = [0..10, 20..30].into_iter().collect();
+        let b: IntervalSet<u32> = [5..25, 28..40].into_iter().collect();
+        assert_eq!(a.union(&b).ranges(), &[0..40]);
+        assert_eq!(a.intersection(&b).ranges(), &[5..10, 20..25, 28..30]);
+    }
+
+    #[test]
+    fn matches_a_set_of_integers() {
+        let mut rng = Rng::new(43);
+        let mut set = IntervalSet::new();
+        let mut expected = [false; 100];
+
+        for _ in 0..500 {
+            let start = rng.range(0..100);
+            let end = rng.range(start..=100);
+            let insert = rng.chance(0.5);
+            if insert {
+                set.insert(start..end);
+            } else {
+                set.remove(start..end);
+            }
+            expected[start as usize..end as usize].fill(insert);
+
+            assert!((0..100).all(|v| set.contains(v) == expected[v as usize]));
+            assert!(set.ranges().windows(2).all(|w| w[0].end < w[1].start));
+        }
+    }
+}
@@ -0,0 +1,10 @@
+//! Data structures that puzzles commonly need and the standard library lacks.
+mod bit_set;
+mod indexed_heap;
+mod interval_set;
+mod union_find;
+
+pub use bit_set::BitSet;
+pub use indexed_heap::IndexedMinHeap;
+pub use interval_set::IntervalSet;
+pub use union_find::UnionFind;
@@ -0,0 +1,131 @@
+/// Disjoint sets of the elements `0..n`, with path compression and union by size.
+///
+/// ```
+/// use advent_of_code::ds::UnionFind;
+///
+/// let mut sets = UnionFind::new(4);
+/// sets.union(0, 1);
+/// sets.union(2, 3);
+/// assert!(sets.connected(1, 0));
+/// assert_eq!(sets.count(), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct UnionFind {
+    parents: Vec<usize>,
+    sizes: Vec<usize>,
+    count: usize,
+}
+
+impl UnionFind {
+    /// `n` singleton sets.
+    pub fn new(n: usize) -> Self {
+        Self {
+            parents: (0..n).collect(),
+            sizes: vec![1; n],
+            count: n,
+        }
+    }
+
+    /// The number of elements.
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    /// The number of disjoint sets.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// The representative of the set containing `x`.
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+
+        let mut x = x;
+        while self.parents[x] != root {
+            x = std::mem::replace(&mut self.parents[x], root);
+        }
+        root
+    }
+
+    /// Merge the sets containing `a` and `b`. Returns `false` if they already were the same set.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+
+        let (large, small) = if self.sizes[a] >= self.sizes[b] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        self.parents[small] = large;
+        self.sizes[large] += self.sizes[small];
+        self.count -= 1;
+        true
+    }
+
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// The size of the set containing `x`.
+    pub fn size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.sizes[root]
+    }
+
+    /// The sizes of all sets, in arbitrary order.
+    pub fn set_sizes(&self) -> Vec<usize> {
+        (0..self.len())
+            .filter(|&x| self.parents[x] == x)
+            .map(|x| self.sizes[x])
+            .collect()
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::UnionFind;
+
+    #[test]
+    fn merges_sets() {
+        let mut sets = UnionFind::new(6);
+        assert_eq!(sets.count(), 6);
+
+        assert!(sets.union(0, 1));
+        assert!(sets.union(1, 2));
+        assert!(!sets.union(2, 0));
+        assert!(sets.union(4, 5));
+
+        assert!(sets.connected(0, 2));
+        assert!(!sets.connected(0, 3));
+        assert_eq!(sets.size(2), 3);
+        assert_eq!(sets.count(), 3);
+
+        let mut sizes = sets.set_sizes();
+        sizes.sort_unstable();
+        assert_eq!(sizes, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn compresses_paths() {
+        let mut sets = UnionFind::new(100);
+        for i in 1..100 {
+            sets.union(i - 1, i);
+        }
+        let root = sets.find(99);
+        assert!((0..100).all(|i| sets.parents[i] == root || sets.find(i) == root));
+        assert_eq!(sets.parents[99], root);
+        assert_eq!(sets.count(), 1);
+    }
+}
@@ -0,0 +1,285 @@
+//! Solver for puzzles that insert operators between a list of operands to reach a target.
+//!
+//! Operators are evaluated strictly left-to-right, without precedence.
+//! Each [`Operator`] has a forward operation and optionally an [`Inverse`]. If all operators have an inverse,
+//! the search works backwards from the target, which prunes most branches early.
+//! Otherwise, all combinations are evaluated forwards.
+//!
+//! ```
+//! use advent_of_code::expr::{self, Operator};
+//!
+//! let expression = expr::solve(190, &[10, 19], &[Operator::add(), Operator::mul()]).unwrap();
+//! assert_eq!(expression.to_string(), "10 * 19");
+//! ```
+use std::fmt::Display;
+
+use crate::math::{self, Integer};
+
+/// An operator that combines the value so far with the next operand.
+#[derive(Debug, Clone, Copy)]
+pub struct Operator<T> {
+    pub symbol: &'static str,
+    /// Computes `left op right`, or `None` if the result is not valid (e.g. it overflows).
+    pub apply: fn(T, T) -> Option<T>,
+    /// Given `result` and `right`, computes the `left` operands with `left op right == result`.
+    pub invert: Option<fn(T, T) -> Inverse<T>>,
+}
+
+/// The left operands that give a result, as computed by the inverse of an [`Operator`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Inverse<T> {
+    /// No left operand gives the result.
+    Unreachable,
+    /// Only this left operand gives the result.
+    Unique(T),
+    /// Every left operand gives the result, e.g. `left * 0 == 0`.
+    Any,
+}
+
+impl<T> From<Option<T>> for Inverse<T> {
+    fn from(left: Option<T>) -> Self {
+        left.map_or(Self::Unreachable, Self::Unique)
+    }
+}
+
+impl<T: Integer> Operator<T> {
+    /// Addition, `a + b`.
+    pub const fn add() -> Self {
+        Self {
+            symbol: "+",
+            apply: T::checked_add,
+            invert: Some(invert_add::<T>),
+        }
+    }
+
+    /// Multiplication, `a * b`.
+    pub const fn mul() -> Self {
+        Self {
+            symbol: "*",
+            apply: T::checked_mul,
+            invert: Some(invert_mul::<T>),
+        }
+    }
+
+    /// Concatenation of the decimal digits, `a || b`. For non-negative operands.
+    pub const fn concat() -> Self {
+        Self {
+            symbol: "||",
+            apply: math::checked_concat::<T>,
+            invert: Some(invert_concat::<T>),
+        }
+    }
+}
+
+fn invert_add<T: Integer>(result: T, right: T) -> Inverse<T> {
+    result.checked_sub(right).into()
+}
+
+fn invert_mul<T: Integer>(result: T, right: T) -> Inverse<T> {
+    match (result == T::ZERO, right == T::ZERO) {
+        (true, true) => Inverse::Any,
+        (false, true) => Inverse::Unreachable,
+        _ => (result % right == T::ZERO).then(|| result / right).into(),
+    }
+}
+
+fn invert_concat<T: Integer>(result: T, right: T) -> Inverse<T> {
+    math::split_concat(result, right).into()
+}
+
+/// An expression reaching the target, the witness returned by [`solve`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expression<T> {
+    pub operands: Vec<T>,
+    /// Symbols of the operators between the operands.
+    pub operators: Vec<&'static str>,
+}
+
+impl<T: Display> Display for Expression<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Some((first, rest)) = self.operands.split_first() else {
+            return Ok(());
+        };
+
+        write!(f, "{first}")?;
+        for (operator, operand) in self.operators.iter().zip(rest) {
+            write!(f, " {operator} {operand}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Find operators between `operands` such that the expression evaluates to `target`.
+/// Returns one such expression, or `None` if the target can not be reached.
+pub fn solve<T: Copy + PartialEq>(
+    target: T,
+    operands: &[T],
+    operators: &[Operator<T>],
+) -> Option<Expression<T>> {
+    let mut chosen = Vec::with_capacity(operands.len().saturating_sub(1));
+
+    let inverses: Option<Vec<_>> = operators.iter().map(|op| op.invert).collect();
+    let found = match inverses {
+        Some(inverses) => search_backwards(target, operands, operators, &inverses, &mut chosen),
+        None => evaluate(operands, Some(target), operators, &mut chosen),
+    };
+
+    found.then(|| Expression {
+        operands: operands.to_vec(),
+        operators: chosen.into_iter().map(|i| operators[i].symbol).collect(),
+    })
+}
+
+/// Returns `true` if the target can be reached, see [`solve`].
+pub fn is_reachable<T: Copy + PartialEq>(
+    target: T,
+    operands: &[T],
+    operators: &[Operator<T>],
+) -> bool {
+    solve(target, operands, operators).is_some()
+}
+
+/// Undo the last operand with each inverse until only the first operand is left.
+/// Pushes the chosen operators in left-to-right order.
+fn search_backwards<T: Copy + PartialEq>(
+    target: T,
+    operands: &[T],
+    operators: &[Operator<T>],
+    inverses: &[fn(T, T) -> Inverse<T>],
+    chosen: &mut Vec<usize>,
+) -> bool {
+    match operands {
+        [] => false,
+        [first] => *first == target,
+        [rest @ .., last] => inverses.iter().enumerate().any(|(i, invert)| {
+            let found = match invert(target, *last) {
+                Inverse::Unreachable => false,
+                Inverse::Unique(left) => search_backwards(left, rest, operators, inverses, chosen),
+                // NOTE: any value of the rest reaches the target, as long as it can be computed.
+                Inverse::Any => evaluate(rest, None, operators, chosen),
+            };
+            if found {
+                chosen.push(i);
+            }
+            found
+        }),
+    }
+}
+
+/// Search forwards for operators between `operands` that evaluate to `target`, or to any value if it is `None`.
+/// Pushes the chosen operators in left-to-right order.
+fn evaluate<T: Copy + PartialEq>(
+    operands: &[T],
+    target: Option<T>,
+    operators: &[Operator<T>],
+    chosen: &mut Vec<usize>,
+) -> bool {
+    let Some((&first, rest)) = operands.split_first() else {
+        return false;
+    };
+
+    let start = chosen.len();
+    let found = search_forwards(first, rest, target, operators, chosen);
+    chosen[start..].reverse();
+    found
+}
+
+/// Apply each operator to the value so far and the next operand.
+/// Pushes the chosen operators in right-to-left order.
+fn search_forwards<T: Copy + PartialEq>(
+    value: T,
+    operands: &[T],
+    target: Option<T>,
+    operators: &[Operator<T>],
+    chosen: &mut Vec<usize>,
+) -> bool {
+    match operands {
+        [] => target.is_none_or(|target| value == target),
+        [next, rest @ ..] => operators.iter().enumerate().any(|(i, op)| {
+            let found = (op.apply)(value, *next)
+                .is_some_and(|value| search_forwards(value, rest, target, operators, chosen));
+            if found {
+                chosen.push(i);
+            }
+            found
+        }),
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{is_reachable, solve, Operator};
+
+    fn forwards<T: crate::math::Integer>(op: Operator<T>) -> Operator<T> {
+        Operator { invert: None, ..op }
+    }
+
+    #[test]
+    fn finds_witness() {
+        let operators = [Operator::add(), Operator::mul()];
+        let expression = solve(3267u64, &[81, 40, 27], &operators).unwrap();
+        // NOTE: "81 + 40 * 27" reaches the target as well, operators are tried in order.
+        assert_eq!(expression.operators, vec!["*", "+"]);
+        assert_eq!(expression.to_string(), "81 * 40 + 27");
+
+        let operators = [Operator::add(), Operator::mul(), Operator::concat()];
+        let expression = solve(7290u64, &[6, 8, 6, 15], &operators).unwrap();
+        assert_eq!(expression.to_string(), "6 * 8 || 6 * 15");
+    }
+
+    #[test]
+    fn rejects_unreachable_targets() {
+        let operators = [Operator::add(), Operator::mul()];
+        assert!(!is_reachable(83u64, &[17, 5], &operators));
+        assert!(!is_reachable(1u64, &[], &operators));
+        assert!(is_reachable(4u64, &[4], &operators));
+    }
+
+    #[test]
+    fn multiplies_by_zero() {
+        let operators = [Operator::add(), Operator::mul()];
+        let expression = solve(0u64, &[5, 0], &operators).unwrap();
+        assert_eq!(expression.to_string(), "5 * 0");
+        let expression = solve(9u64, &[5, 7, 0, 9], &operators).unwrap();
+        assert_eq!(expression.to_string(), "5 + 7 * 0 + 9");
+        assert!(!is_reachable(
+            0u64,
+            &[10_000_000_000_000_000_000, 10_000_000_000_000_000_000, 0],
+            &operators
+        ));
+    }
+
+    #[test]
+    fn searches_forwards_without_inverses() {
+        let operators = [forwards(Operator::add()), Operator::mul()];
+        let expression = solve(292u32, &[11, 6, 16, 20], &operators).unwrap();
+        assert_eq!(expression.to_string(), "11 + 6 * 16 + 20");
+        assert!(!is_reachable(21037u32, &[9, 7, 18, 13], &operators));
+    }
+
+    #[test]
+    fn search_directions_agree() {
+        let mut rng = crate::rng::Rng::new(7);
+        let backwards = [Operator::add(), Operator::mul(), Operator::concat()];
+        let forwards = backwards.map(forwards);
+
+        for _ in 0..200 {
+            let operands: Vec<u64> = (0..4).map(|_| rng.range(0..10)).collect();
+            let target = rng.range(0..200);
+            assert_eq!(
+                is_reachable(target, &operands, &backwards),
+                is_reachable(target, &operands, &forwards),
+                "{target}: {operands:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn avoids_overflow() {
+        let operators = [Operator::add(), Operator::mul()].map(forwards);
+        assert!(!is_reachable(0u32, &[70_000, 70_000], &operators));
+        assert!(is_reachable(u32::MAX, &[65_535, 65_537], &operators));
+    }
+}
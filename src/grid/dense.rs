@@ -0,0 +1,351 @@
+use std::fmt::Display;
+use std::ops::{Index, IndexMut};
+
+use super::Point;
+
+/// A rectangular grid of cells, stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// A grid with every cell set to `value`.
+    pub fn new(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// A grid from its cells in reading order.
+    ///
+    /// # Panics
+    /// If the number of cells is not `width * height`.
+    pub fn from_cells(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(
+            cells.len(),
+            width * height,
+            "expected {width}x{height} cells"
+        );
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Parse a grid with one line per row, mapping each character to a cell.
+    ///
+    /// # Panics
+    /// If the lines are not all of the same length.
+    pub fn parse(input: &str, mut cell: impl FnMut(char) -> T) -> Self {
+        let mut width = None;
+        let mut cells = vec![];
+        let mut height = 0;
+
+        for line in input.lines().filter(|line| !line.is_empty()) {
+            let len = cells.len();
+            cells.extend(line.chars().map(&mut cell));
+            let row_width = cells.len() - len;
+            assert_eq!(
+                *width.get_or_insert(row_width),
+                row_width,
+                "rows must be of the same length"
+            );
+            height += 1;
+        }
+
+        Self {
+            width: width.unwrap_or_default(),
+            height,
+            cells,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns `true` if `point` lies within the grid.
+    pub fn contains(&self, point: Point) -> bool {
+        self.index_of(point).is_some()
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.index_of(point).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.index_of(point).map(|i| &mut self.cells[i])
+    }
+
+    /// All points of the grid in reading order.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+        (0..self.width * self.height).map(move |i| Self::point_of(width, i))
+    }
+
+    /// All cells with their points in reading order.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| (Self::point_of(width, i), cell))
+    }
+
+    /// The rows of the grid, top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // NOTE: `chunks` panics for a width of zero.
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// The first point whose cell satisfies `predicate`.
+    pub fn position(&self, predicate: impl FnMut(&T) -> bool) -> Option<Point> {
+        self.cells
+            .iter()
+            .position(predicate)
+            .map(|i| Self::point_of(self.width, i))
+    }
+
+    /// A grid of the same size with `f` applied to every cell.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// The grid rotated by 90 degrees clockwise.
+    pub fn rotate_cw(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.transform(self.height, self.width, |x, y| (y, self.height - 1 - x))
+    }
+
+    /// The grid mirrored from left to right.
+    pub fn flip_horizontal(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.transform(self.width, self.height, |x, y| (self.width - 1 - x, y))
+    }
+
+    /// The distinct rotations and reflections of the grid, starting with the grid itself.
+    pub fn orientations(&self) -> Vec<Self>
+    where
+        T: Clone + PartialEq,
+    {
+        let mut orientations: Vec<Self> = vec![];
+        let mut grid = self.clone();
+
+        for _ in 0..4 {
+            for candidate in [grid.clone(), grid.flip_horizontal()] {
+                if !orientations.contains(&candidate) {
+                    orientations.push(candidate);
+                }
+            }
+            grid = grid.rotate_cw();
+        }
+
+        orientations
+    }
+
+    /// A `width` by `height` grid whose cell at `(x, y)` is the cell of this grid at `source(x, y)`.
+    fn transform(
+        &self,
+        width: usize,
+        height: usize,
+        source: impl Fn(usize, usize) -> (usize, usize),
+    ) -> Self
+    where
+        T: Clone,
+    {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let (sx, sy) = source(x, y);
+                self.cells[sy * self.width + sx].clone()
+            })
+            .collect();
+        Self::from_cells(width, height, cells)
+    }
+
+    fn index_of(&self, point: Point) -> Option<usize> {
+        let x = usize::try_from(point.x).ok()?;
+        let y = usize::try_from(point.y).ok()?;
+        (x < self.width && y < self.height).then(|| y * self.width + x)
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    fn point_of(width: usize, index: usize) -> Point {
+        Point::new((index % width) as i32, (index / width) as i32)
+    }
+}
+
+impl<T: PartialEq> Grid<T> {
+    /// Returns `true` if `word` can be read starting at `start`, moving in `direction`.
+    pub fn matches_word(&self, word: &[T], start: Point, direction: Point) -> bool {
+        let mut point = start;
+        word.iter().all(|expected| {
+            let matches = self.get(point) == Some(expected);
+            point += direction;
+            matches
+        })
+    }
+
+    /// The start points and directions of all occurrences of `word`, read in any of `directions`.
+    /// Palindromes are found once for each direction they can be read in.
+    pub fn find_word<'a>(
+        &'a self,
+        word: &'a [T],
+        directions: &'a [Point],
+    ) -> impl Iterator<Item = (Point, Point)> + 'a {
+        self.iter()
+            .filter(move |(_, cell)| word.first() == Some(cell))
+            .flat_map(move |(start, _)| directions.iter().map(move |&d| (start, d)))
+            .filter(move |&(start, direction)| self.matches_word(word, start, direction))
+    }
+
+    /// The number of occurrences of `word`, see [`Grid::find_word`].
+    pub fn count_word(&self, word: &[T], directions: &[Point]) -> usize {
+        self.find_word(word, directions).count()
+    }
+
+    /// Returns `true` if the template matches with its top left corner at `at`.
+    /// `None` cells of the template match any cell.
+    pub fn matches_template(&self, template: &Grid<Option<T>>, at: Point) -> bool {
+        template.iter().all(|(offset, expected)| match expected {
+            Some(expected) => self.get(at + offset) == Some(expected),
+            None => self.contains(at + offset),
+        })
+    }
+
+    /// The top left corners of all matches of the template, see [`Grid::matches_template`].
+    pub fn find_template<'a>(
+        &'a self,
+        template: &'a Grid<Option<T>>,
+    ) -> impl Iterator<Item = Point> + 'a {
+        self.points()
+            .filter(move |&at| self.matches_template(template, at))
+    }
+
+    /// The number of matches of the template in any of its distinct rotations and reflections.
+    pub fn count_template_orientations(&self, template: &Grid<Option<T>>) -> usize
+    where
+        T: Clone,
+    {
+        template
+            .orientations()
+            .iter()
+            .map(|template| self.find_template(template).count())
+            .sum()
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        self.get(point)
+            .unwrap_or_else(|| panic!("{point} is outside the grid"))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        self.get_mut(point)
+            .unwrap_or_else(|| panic!("{point} is outside the grid"))
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Grid;
+    use crate::grid::Point;
+
+    fn template(text: &str) -> Grid<Option<char>> {
+        Grid::parse(text, |c| (c != '.').then_some(c))
+    }
+
+    #[test]
+    fn parses_and_indexes() {
+        let grid = Grid::parse("ab\ncd\nef\n", |c| c);
+        assert_eq!((grid.width(), grid.height()), (2, 3));
+        assert_eq!(grid[Point::new(1, 2)], 'f');
+        assert_eq!(grid.get(Point::new(2, 0)), None);
+        assert_eq!(grid.get(Point::new(0, -1)), None);
+        assert_eq!(grid.position(|&c| c == 'd'), Some(Point::new(1, 1)));
+        assert_eq!(grid.to_string(), "ab\ncd\nef");
+    }
+
+    #[test]
+    fn transforms_grids() {
+        let grid = Grid::parse("ab\ncd\nef", |c| c);
+        assert_eq!(grid.rotate_cw().to_string(), "eca\nfdb");
+        assert_eq!(grid.flip_horizontal().to_string(), "ba\ndc\nfe");
+        assert_eq!(grid.orientations().len(), 8);
+        assert_eq!(grid.orientations()[0], grid);
+
+        let symmetric = Grid::parse("ab\nba", |c| c);
+        assert_eq!(symmetric.orientations().len(), 2);
+    }
+
+    #[test]
+    fn finds_words_in_all_directions() {
+        let grid = Grid::parse("T...T\n.E.E.\n..S..\n.T.T.", |c| c);
+        let word = ['T', 'E', 'S', 'T'];
+        let found: Vec<_> = grid.find_word(&word, &Point::DIRECTIONS).collect();
+        assert_eq!(
+            found,
+            vec![
+                (Point::new(0, 0), Point::new(1, 1)),
+                (Point::new(4, 0), Point::new(-1, 1)),
+            ]
+        );
+        assert_eq!(grid.count_word(&word, &Point::ORTHOGONAL), 0);
+    }
+
+    #[test]
+    fn matches_templates_with_wildcards() {
+        let grid = Grid::parse("M.S.\n.A..\nM.S.\n....", |c| c);
+        let cross = template("M.S\n.A.\nM.S");
+        assert_eq!(
+            grid.find_template(&cross).collect::<Vec<_>>(),
+            vec![Point::ZERO]
+        );
+
+        let rotated = grid.rotate_cw().rotate_cw();
+        assert_eq!(rotated.find_template(&cross).count(), 0);
+        assert_eq!(rotated.count_template_orientations(&cross), 1);
+    }
+}
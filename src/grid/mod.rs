@@ -0,0 +1,21 @@
+//! 2D grids and pattern matching on them.
+//!
+//! Points are signed, so that directions and offsets are points as well. `x` grows to the right, `y` grows downwards.
+//! [`Grid`] is a dense rectangular map, [`SparseGrid`] stores only occupied points and can grow in any direction.
+//! Patterns are either words read in a direction, see [`Grid::find_word`], or small 2D templates
+//! with wildcard cells, see [`Grid::find_template`].
+//!
+//! ```
+//! use advent_of_code::grid::{Grid, Point};
+//!
+//! let grid = Grid::parse("XMAS\n.A..\nS..S", |c| c);
+//! assert_eq!(grid[Point::new(1, 1)], 'A');
+//! assert_eq!(grid.count_word(&['X', 'M', 'A', 'S'], &Point::DIRECTIONS), 1);
+//! ```
+mod dense;
+mod point;
+mod sparse;
+
+pub use dense::Grid;
+pub use point::Point;
+pub use sparse::{Bounds, SparseGrid};
@@ -0,0 +1,120 @@
+use std::fmt::Display;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// A point or direction on a grid.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    pub const ZERO: Self = Self::new(0, 0);
+    pub const UP: Self = Self::new(0, -1);
+    pub const DOWN: Self = Self::new(0, 1);
+    pub const LEFT: Self = Self::new(-1, 0);
+    pub const RIGHT: Self = Self::new(1, 0);
+
+    /// The four orthogonal directions, clockwise starting upwards.
+    pub const ORTHOGONAL: [Self; 4] = [Self::UP, Self::RIGHT, Self::DOWN, Self::LEFT];
+
+    /// The eight orthogonal and diagonal directions, clockwise starting upwards.
+    pub const DIRECTIONS: [Self; 8] = [
+        Self::UP,
+        Self::new(1, -1),
+        Self::RIGHT,
+        Self::new(1, 1),
+        Self::DOWN,
+        Self::new(-1, 1),
+        Self::LEFT,
+        Self::new(-1, -1),
+    ];
+
+    pub const fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+
+    /// Rotate a direction by 90 degrees clockwise.
+    pub const fn rotate_cw(self) -> Self {
+        Self::new(-self.y, self.x)
+    }
+
+    /// Rotate a direction by 90 degrees counter-clockwise.
+    pub const fn rotate_ccw(self) -> Self {
+        Self::new(self.y, -self.x)
+    }
+
+    pub const fn manhattan(self, other: Self) -> u32 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// The neighbouring points in the given directions.
+    pub fn neighbours(self, directions: &[Self]) -> impl Iterator<Item = Self> + '_ {
+        directions.iter().map(move |&d| self + d)
+    }
+}
+
+impl Add for Point {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Point {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul<i32> for Point {
+    type Output = Self;
+
+    fn mul(self, rhs: i32) -> Self {
+        Self::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl Neg for Point {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+impl Display for Point {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Point;
+
+    #[test]
+    fn rotates_points() {
+        assert_eq!(Point::UP.rotate_cw(), Point::RIGHT);
+        assert_eq!(Point::UP.rotate_ccw(), Point::LEFT);
+        assert_eq!(Point::new(1, 2).manhattan(Point::new(-2, 0)), 5);
+        assert_eq!(Point::new(1, 2) * 3 - Point::new(1, 1), Point::new(2, 5));
+    }
+}
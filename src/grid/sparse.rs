@@ -0,0 +1,284 @@
+use std::fmt::Display;
+
+use super::Point;
+use crate::hash::FastMap;
+
+/// An inclusive rectangle of points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Bounds {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl Bounds {
+    /// The rectangle spanned by two corners.
+    pub fn new(a: Point, b: Point) -> Self {
+        Self {
+            min: Point::new(a.x.min(b.x), a.y.min(b.y)),
+            max: Point::new(a.x.max(b.x), a.y.max(b.y)),
+        }
+    }
+
+    /// The rectangle of a `width` by `height` map with its top left corner at the origin.
+    ///
+    /// # Panics
+    /// If the map is empty.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    pub fn from_size(width: usize, height: usize) -> Self {
+        assert!(width > 0 && height > 0, "bounds can not be empty");
+        Self::new(Point::ZERO, Point::new(width as i32 - 1, height as i32 - 1))
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x) && (self.min.y..=self.max.y).contains(&point.y)
+    }
+
+    pub fn width(&self) -> usize {
+        self.max.x.abs_diff(self.min.x) as usize + 1
+    }
+
+    pub fn height(&self) -> usize {
+        self.max.y.abs_diff(self.min.y) as usize + 1
+    }
+
+    /// The smallest rectangle containing this one and `point`.
+    pub fn expand(self, point: Point) -> Self {
+        Self {
+            min: Point::new(self.min.x.min(point.x), self.min.y.min(point.y)),
+            max: Point::new(self.max.x.max(point.x), self.max.y.max(point.y)),
+        }
+    }
+
+    /// All points of the rectangle in reading order.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let Self { min, max } = *self;
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| Point::new(x, y)))
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// An unbounded grid that only stores occupied points, for sets of points and maps that grow.
+/// The bounding box of the occupied points is kept up to date.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: FastMap<Point, T>,
+    bounds: Option<Bounds>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self {
+            cells: FastMap::default(),
+            bounds: None,
+        }
+    }
+
+    /// Parse a map with one line per row, keeping the characters that `cell` maps to `Some`.
+    pub fn parse(input: &str, mut cell: impl FnMut(char) -> Option<T>) -> Self {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+        input
+            .lines()
+            .enumerate()
+            .flat_map(|(y, line)| {
+                line.chars()
+                    .enumerate()
+                    .map(move |(x, c)| (Point::new(x as i32, y as i32), c))
+            })
+            .filter_map(|(point, c)| cell(c).map(|value| (point, value)))
+            .collect()
+    }
+
+    /// The number of occupied points.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// The smallest rectangle containing all occupied points, `None` if the grid is empty.
+    pub fn bounds(&self) -> Option<Bounds> {
+        self.bounds
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        self.cells.contains_key(&point)
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.cells.get(&point)
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.cells.get_mut(&point)
+    }
+
+    /// Set the value at `point`, returning the previous value.
+    pub fn insert(&mut self, point: Point, value: T) -> Option<T> {
+        self.bounds = Some(match self.bounds {
+            Some(bounds) => bounds.expand(point),
+            None => Bounds::new(point, point),
+        });
+        self.cells.insert(point, value)
+    }
+
+    /// Remove the value at `point`, shrinking the bounds if needed.
+    pub fn remove(&mut self, point: Point) -> Option<T> {
+        let value = self.cells.remove(&point)?;
+
+        let on_edge = self.bounds.is_some_and(|Bounds { min, max }| {
+            point.x == min.x || point.x == max.x || point.y == min.y || point.y == max.y
+        });
+        if on_edge {
+            self.bounds = self.cells.keys().fold(None, |bounds: Option<Bounds>, &p| {
+                Some(bounds.map_or(Bounds::new(p, p), |b| b.expand(p)))
+            });
+        }
+
+        Some(value)
+    }
+
+    /// The occupied points with their values in reading order.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        let mut points: Vec<Point> = self.cells.keys().copied().collect();
+        points.sort_unstable_by_key(|p| (p.y, p.x));
+        points.into_iter().map(|p| (p, &self.cells[&p]))
+    }
+
+    /// The occupied neighbours of `point` in the given directions.
+    pub fn neighbours<'a>(
+        &'a self,
+        point: Point,
+        directions: &'a [Point],
+    ) -> impl Iterator<Item = (Point, &'a T)> + 'a {
+        point
+            .neighbours(directions)
+            .filter_map(|p| self.get(p).map(|value| (p, value)))
+    }
+
+    /// Draw the grid within its bounds, unoccupied points are drawn as `empty`.
+    pub fn render(&self, empty: char, cell: impl FnMut(&T) -> char) -> String {
+        match self.bounds {
+            Some(bounds) => self.render_within(bounds, empty, cell),
+            None => String::new(),
+        }
+    }
+
+    /// Draw the points within `bounds`, e.g. the whole map of a puzzle.
+    pub fn render_within(
+        &self,
+        bounds: Bounds,
+        empty: char,
+        mut cell: impl FnMut(&T) -> char,
+    ) -> String {
+        let mut text = String::with_capacity((bounds.width() + 1) * bounds.height());
+        for point in bounds.points() {
+            if point.x == bounds.min.x && point.y != bounds.min.y {
+                text.push('\n');
+            }
+            text.push(self.get(point).map_or(empty, &mut cell));
+        }
+        text
+    }
+}
+
+impl<T> FromIterator<(Point, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point, T)>>(iter: I) -> Self {
+        let mut grid = Self::new();
+        grid.extend(iter);
+        grid
+    }
+}
+
+impl<T> Extend<(Point, T)> for SparseGrid<T> {
+    fn extend<I: IntoIterator<Item = (Point, T)>>(&mut self, iter: I) {
+        for (point, value) in iter {
+            self.insert(point, value);
+        }
+    }
+}
+
+/// Draws the occupied points within the bounds, unoccupied points as `.`.
+impl Display for SparseGrid<char> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.render('.', |&c| c))
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Bounds, SparseGrid};
+    use crate::grid::Point;
+
+    #[test]
+    fn tracks_bounds() {
+        let mut grid = SparseGrid::new();
+        assert_eq!(grid.bounds(), None);
+
+        grid.insert(Point::new(2, 3), 'a');
+        grid.insert(Point::new(-1, 5), 'b');
+        grid.insert(Point::new(0, 0), 'c');
+        assert_eq!(
+            grid.bounds(),
+            Some(Bounds::new(Point::new(-1, 0), Point::new(2, 5)))
+        );
+
+        assert_eq!(grid.remove(Point::new(-1, 5)), Some('b'));
+        assert_eq!(grid.remove(Point::new(-1, 5)), None);
+        assert_eq!(
+            grid.bounds(),
+            Some(Bounds::new(Point::new(0, 0), Point::new(2, 3)))
+        );
+
+        grid.remove(Point::new(0, 0));
+        grid.remove(Point::new(2, 3));
+        assert_eq!(grid.bounds(), None);
+    }
+
+    #[test]
+    fn iterates_in_reading_order() {
+        let grid = SparseGrid::parse("..b\na..\n.c.", |c| (c != '.').then_some(c));
+        let cells: Vec<_> = grid.iter().map(|(p, &c)| (p, c)).collect();
+        assert_eq!(
+            cells,
+            vec![
+                (Point::new(2, 0), 'b'),
+                (Point::new(0, 1), 'a'),
+                (Point::new(1, 2), 'c'),
+            ]
+        );
+
+        let neighbours: Vec<_> = grid
+            .neighbours(Point::new(1, 1), &Point::DIRECTIONS)
+            .map(|(_, &c)| c)
+            .collect();
+        assert_eq!(neighbours, vec!['b', 'c', 'a']);
+    }
+
+    #[test]
+    fn renders_points() {
+        let grid: SparseGrid<char> = [(Point::new(-1, -1), '#'), (Point::new(1, 0), '@')]
+            .into_iter()
+            .collect();
+        assert_eq!(grid.to_string(), "#..\n..@");
+        assert_eq!(
+            grid.render_within(Bounds::from_size(2, 2), ' ', |_| 'x'),
+            " x\n  "
+        );
+
+        let bounds = Bounds::new(Point::new(3, -2), Point::new(-1, 0));
+        assert_eq!((bounds.width(), bounds.height()), (5, 3));
+        assert!(bounds.contains(Point::new(0, -1)));
+        assert!(!bounds.contains(Point::new(0, 1)));
+    }
+}
@@ -0,0 +1,117 @@
+//! A fast, deterministic hasher for small keys such as integers and points.
+//!
+//! This is the FxHash algorithm used by rustc: it is much faster than the standard library's SipHash,
+//! but not resistant against collisions crafted on purpose, which is fine for puzzle inputs.
+//!
+//! [`FastMap`] and [`FastSet`] are the standard collections using this hasher. Create them with `default()`
+//! or by collecting, `new()` is only available for the standard hasher.
+use std::collections::{HashMap, HashSet};
+use std::hash::{BuildHasherDefault, Hasher};
+
+const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+/// The FxHash hasher.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FxHasher {
+    hash: u64,
+}
+
+impl FxHasher {
+    fn add_to_hash(&mut self, word: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(SEED);
+    }
+}
+
+impl Hasher for FxHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            self.add_to_hash(u64::from_le_bytes(chunk.try_into().unwrap()));
+        }
+        let rest = chunks.remainder();
+        if !rest.is_empty() {
+            let mut word = [0; 8];
+            word[..rest.len()].copy_from_slice(rest);
+            self.add_to_hash(u64::from_le_bytes(word));
+        }
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.add_to_hash(u64::from(i));
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.add_to_hash(u64::from(i));
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.add_to_hash(u64::from(i));
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.add_to_hash(i);
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.add_to_hash(i as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+/// Builds [`FxHasher`]s, for use as the hasher of `HashMap` and `HashSet`.
+pub type FxBuildHasher = BuildHasherDefault<FxHasher>;
+
+/// A `HashMap` using [`FxHasher`].
+pub type FastMap<K, V> = HashMap<K, V, FxBuildHasher>;
+
+/// A `HashSet` using [`FxHasher`].
+pub type FastSet<T> = HashSet<T, FxBuildHasher>;
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{FastMap, FastSet, FxBuildHasher, FxHasher};
+    use std::collections::HashSet;
+    use std::hash::{BuildHasher, Hasher};
+
+    #[test]
+    fn is_deterministic() {
+        let hash = |value: (i32, i32)| FxBuildHasher::default().hash_one(value);
+        assert_eq!(hash((1, 2)), hash((1, 2)));
+        assert_ne!(hash((1, 2)), hash((2, 1)));
+    }
+
+    #[test]
+    fn hashes_unaligned_bytes() {
+        let hash = |bytes: &[u8]| {
+            let mut hasher = FxHasher::default();
+            hasher.write(bytes);
+            hasher.finish()
+        };
+        assert_ne!(hash(b"abcdefghi"), hash(b"abcdefghj"));
+        assert_ne!(hash(b""), hash(b"a"));
+    }
+
+    #[test]
+    fn works_as_collections() {
+        let mut map: FastMap<(i32, i32), char> = FastMap::default();
+        map.insert((0, 0), 'a');
+        map.insert((0, 1), 'b');
+        assert_eq!(map.get(&(0, 1)), Some(&'b'));
+
+        let set: FastSet<u32> = [1, 2, 2, 3].into_iter().collect();
+        assert_eq!(set.len(), 3);
+    }
+
+    #[test]
+    fn spreads_small_keys() {
+        let hashes: HashSet<u64> = (0..10_000u32)
+            .map(|i| FxBuildHasher::default().hash_one(i))
+            .collect();
+        assert_eq!(hashes.len(), 10_000);
+    }
+}
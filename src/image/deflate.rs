@@ -0,0 +1,299 @@
+//! A small DEFLATE encoder with fixed Huffman codes and greedy LZ77 matching, wrapped in a zlib stream.
+//! Grids have long runs of equal pixels, which compress well even without dynamic codes.
+
+const WINDOW: usize = 32 * 1024;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+/// How many earlier positions with the same three bytes are tried for a match.
+const MAX_CHAIN: usize = 32;
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+/// Writes bits least significant bit first, as DEFLATE and GIF expect.
+#[derive(Default)]
+pub(super) struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    len: u32,
+}
+
+impl BitWriter {
+    /// Write the lowest `count` bits of `bits`.
+    pub(super) fn write(&mut self, bits: u32, count: u32) {
+        debug_assert!(count <= 24);
+        self.buffer |= bits << self.len;
+        self.len += count;
+        while self.len >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.len -= 8;
+        }
+    }
+
+    /// Write a Huffman code, which is stored most significant bit first.
+    fn write_code(&mut self, code: u32, count: u32) {
+        self.write(code.reverse_bits() >> (32 - count), count);
+    }
+
+    /// Pad the last byte with zeros.
+    pub(super) fn finish(mut self) -> Vec<u8> {
+        if self.len > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+/// Compress `data` into a zlib stream.
+pub(super) fn zlib(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+    out.extend(deflate(data));
+    out.extend(adler32(data).to_be_bytes());
+    out
+}
+
+/// Compress `data` into a single fixed Huffman block.
+pub(super) fn deflate(data: &[u8]) -> Vec<u8> {
+    let mut bits = BitWriter::default();
+    bits.write(1, 1); // BFINAL
+    bits.write(1, 2); // BTYPE: fixed Huffman codes
+
+    let mut matcher = Matcher::new(data);
+    let mut i = 0;
+    while i < data.len() {
+        let (length, distance) = matcher.longest_match(i);
+
+        if length >= MIN_MATCH {
+            write_length(&mut bits, length);
+            write_distance(&mut bits, distance);
+            for j in i..i + length {
+                matcher.insert(j);
+            }
+            i += length;
+        } else {
+            write_literal(&mut bits, u16::from(data[i]));
+            matcher.insert(i);
+            i += 1;
+        }
+    }
+
+    write_literal(&mut bits, 256);
+    bits.finish()
+}
+
+/// Finds earlier occurrences of the bytes at a position through chains of positions with the same 3-byte hash.
+struct Matcher<'a> {
+    data: &'a [u8],
+    /// The last position of every hash.
+    head: Vec<usize>,
+    /// The previous position with the same hash as a position.
+    previous: Vec<usize>,
+}
+
+impl<'a> Matcher<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            head: vec![usize::MAX; 1 << 15],
+            previous: vec![usize::MAX; data.len()],
+        }
+    }
+
+    fn hash(&self, i: usize) -> usize {
+        let data = self.data;
+        ((usize::from(data[i]) << 10) ^ (usize::from(data[i + 1]) << 5) ^ usize::from(data[i + 2]))
+            & 0x7fff
+    }
+
+    fn insert(&mut self, i: usize) {
+        if i + MIN_MATCH <= self.data.len() {
+            let hash = self.hash(i);
+            self.previous[i] = self.head[hash];
+            self.head[hash] = i;
+        }
+    }
+
+    /// The longest earlier match of the bytes at `i`, as a length and a distance.
+    fn longest_match(&self, i: usize) -> (usize, usize) {
+        let data = self.data;
+        if i + MIN_MATCH > data.len() {
+            return (0, 0);
+        }
+
+        let max = MAX_MATCH.min(data.len() - i);
+        let mut best = (0, 0);
+        let mut candidate = self.head[self.hash(i)];
+
+        for _ in 0..MAX_CHAIN {
+            if candidate == usize::MAX || i - candidate > WINDOW {
+                break;
+            }
+            let length = data[candidate..]
+                .iter()
+                .zip(&data[i..i + max])
+                .take_while(|(a, b)| a == b)
+                .count();
+            if length > best.0 {
+                best = (length, i - candidate);
+                if length == max {
+                    break;
+                }
+            }
+            candidate = self.previous[candidate];
+        }
+        best
+    }
+}
+
+fn write_literal(bits: &mut BitWriter, value: u16) {
+    let value = u32::from(value);
+    match value {
+        0..=143 => bits.write_code(0x30 + value, 8),
+        144..=255 => bits.write_code(0x190 + value - 144, 9),
+        256..=279 => bits.write_code(value - 256, 7),
+        _ => bits.write_code(0xc0 + value - 280, 8),
+    }
+}
+
+fn write_length(bits: &mut BitWriter, length: usize) {
+    let index = LENGTH_BASE.partition_point(|&base| usize::from(base) <= length) - 1;
+    write_literal(bits, 257 + index as u16);
+    bits.write(
+        (length - usize::from(LENGTH_BASE[index])) as u32,
+        u32::from(LENGTH_EXTRA[index]),
+    );
+}
+
+fn write_distance(bits: &mut BitWriter, distance: usize) {
+    let index = DISTANCE_BASE.partition_point(|&base| usize::from(base) <= distance) - 1;
+    bits.write_code(index as u32, 5);
+    bits.write(
+        (distance - usize::from(DISTANCE_BASE[index])) as u32,
+        u32::from(DISTANCE_EXTRA[index]),
+    );
+}
+
+pub(super) fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    // NOTE: 5552 is the largest chunk for which the sums can not overflow before the modulo.
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += u32::from(byte);
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    (b << 16) | a
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{adler32, deflate, DISTANCE_BASE, DISTANCE_EXTRA, LENGTH_BASE, LENGTH_EXTRA};
+    use crate::rng::Rng;
+
+    /// Reads bits least significant bit first.
+    struct BitReader<'a> {
+        bytes: &'a [u8],
+        position: usize,
+    }
+
+    impl BitReader<'_> {
+        fn bits(&mut self, count: usize) -> usize {
+            (0..count).fold(0, |value, i| {
+                let bit = (self.bytes[self.position / 8] >> (self.position % 8)) & 1;
+                self.position += 1;
+                value | usize::from(bit) << i
+            })
+        }
+
+        /// Read a fixed Huffman code of the literal/length alphabet.
+        fn literal(&mut self) -> usize {
+            let mut code = 0;
+            for len in 1..=9 {
+                code = code << 1 | self.bits(1);
+                match (len, code) {
+                    (7, 0..=0x17) => return code + 256,
+                    (8, 0x30..=0xbf) => return code - 0x30,
+                    (8, 0xc0..=0xc7) => return code - 0xc0 + 280,
+                    (9, 0x190..=0x1ff) => return code - 0x190 + 144,
+                    _ => {}
+                }
+            }
+            unreachable!("invalid code {code:b}")
+        }
+    }
+
+    /// Decodes a single fixed Huffman block.
+    fn inflate(bytes: &[u8]) -> Vec<u8> {
+        let mut reader = BitReader { bytes, position: 0 };
+        assert_eq!(
+            reader.bits(3),
+            0b011,
+            "expected a final fixed Huffman block"
+        );
+
+        let mut out: Vec<u8> = vec![];
+        loop {
+            match reader.literal() {
+                literal @ 0..=255 => out.push(literal as u8),
+                256 => return out,
+                symbol => {
+                    let index = symbol - 257;
+                    let length = usize::from(LENGTH_BASE[index])
+                        + reader.bits(usize::from(LENGTH_EXTRA[index]));
+                    let index = (0..5).fold(0, |code, _| code << 1 | reader.bits(1));
+                    let distance = usize::from(DISTANCE_BASE[index])
+                        + reader.bits(usize::from(DISTANCE_EXTRA[index]));
+                    for _ in 0..length {
+                        out.push(out[out.len() - distance]);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn round_trips() {
+        let mut rng = Rng::new(5);
+        let inputs: Vec<Vec<u8>> = vec![
+            vec![],
+            b"a".to_vec(),
+            b"abcabcabcabcabcabcabc".to_vec(),
+            vec![7; 100_000],
+            (0..20_000).map(|_| rng.range(0u8..4)).collect(),
+            (0..5_000).map(|_| rng.range(0..=u8::MAX)).collect(),
+        ];
+
+        for input in inputs {
+            assert_eq!(inflate(&deflate(&input)), input, "{} bytes", input.len());
+        }
+    }
+
+    #[test]
+    fn compresses_runs() {
+        assert!(deflate(&vec![0; 100_000]).len() < 1_000);
+    }
+
+    #[test]
+    fn computes_adler32() {
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+        assert_eq!(adler32(&[]), 1);
+    }
+}
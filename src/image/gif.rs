@@ -0,0 +1,277 @@
+//! GIF encoding of animations, with a global palette of at most 256 colours.
+//! Every frame after the first only stores the rectangle of pixels that changed.
+use super::deflate::BitWriter;
+use super::{Image, ImageError, Rgb};
+use crate::hash::FastMap;
+
+const MAX_CODES: u16 = 4096;
+
+/// Encode frames of the same size, shown for `delay` hundredths of a second each and looped forever.
+pub(super) fn encode(frames: &[Image], delay: u16) -> Result<Vec<u8>, ImageError> {
+    let Some(first) = frames.first() else {
+        return Err(ImageError::NoFrames);
+    };
+    let (width, height) = (first.width(), first.height());
+    if let Some(frame) = frames
+        .iter()
+        .find(|frame| (frame.width(), frame.height()) != (width, height))
+    {
+        return Err(ImageError::SizeMismatch {
+            expected: (width, height),
+            found: (frame.width(), frame.height()),
+        });
+    }
+
+    let (Ok(width16), Ok(height16)) = (u16::try_from(width), u16::try_from(height)) else {
+        return Err(ImageError::TooLarge { width, height });
+    };
+
+    let palette = palette(frames)?;
+    // NOTE: the palette has `2^bits` entries, at least two.
+    let bits = (usize::BITS - (palette.len().max(2) - 1).leading_zeros()).max(1);
+
+    let mut out = b"GIF89a".to_vec();
+    out.extend(width16.to_le_bytes());
+    out.extend(height16.to_le_bytes());
+    // NOTE: global colour table, 8 bit colour resolution, table size `2^(n + 1)`.
+    out.extend([0xf0 | (bits - 1) as u8, 0, 0]);
+    for i in 0..1 << bits {
+        let color = palette.get(i).copied().unwrap_or_default();
+        out.extend([color.r, color.g, color.b]);
+    }
+    // Loop forever.
+    out.extend(b"\x21\xff\x0bNETSCAPE2.0\x03\x01\x00\x00\x00");
+
+    let indices: FastMap<Rgb, u8> = palette
+        .iter()
+        .enumerate()
+        .map(|(i, &color)| (color, i as u8))
+        .collect();
+
+    let mut previous: Option<&Image> = None;
+    for frame in frames {
+        let (left, top, right, bottom) = match previous {
+            Some(previous) => changed_rectangle(previous, frame),
+            None => (0, 0, width, height),
+        };
+        previous = Some(frame);
+
+        // Graphic control extension: keep the previous frame below this one.
+        out.extend([0x21, 0xf9, 0x04, 0x04]);
+        out.extend(delay.to_le_bytes());
+        out.extend([0, 0]);
+
+        out.push(0x2c);
+        for value in [left, top, right - left, bottom - top] {
+            let value = u16::try_from(value).expect("the rectangle is within the checked size");
+            out.extend(value.to_le_bytes());
+        }
+        out.push(0);
+
+        let pixels = frame
+            .pixels
+            .rows()
+            .skip(top)
+            .take(bottom - top)
+            .flat_map(|row| row[left..right].iter().map(|color| indices[color]));
+        let min_code_size = bits.max(2);
+        out.push(min_code_size as u8);
+        for block in lzw(pixels, min_code_size).chunks(255) {
+            out.push(block.len() as u8);
+            out.extend(block);
+        }
+        out.push(0);
+    }
+
+    out.push(0x3b);
+    Ok(out)
+}
+
+/// The distinct colours of all frames, in order of appearance.
+fn palette(frames: &[Image]) -> Result<Vec<Rgb>, ImageError> {
+    let mut palette = vec![];
+    let mut seen = FastMap::default();
+    for frame in frames {
+        for row in frame.pixels.rows() {
+            for &color in row {
+                seen.entry(color).or_insert_with(|| palette.push(color));
+            }
+        }
+    }
+
+    if palette.len() > 256 {
+        return Err(ImageError::TooManyColors(palette.len()));
+    }
+    Ok(palette)
+}
+
+/// The smallest rectangle containing the pixels that differ, as `(left, top, right, bottom)` with exclusive ends.
+/// At least one pixel, as every frame needs an image.
+fn changed_rectangle(previous: &Image, frame: &Image) -> (usize, usize, usize, usize) {
+    let mut rectangle: Option<(usize, usize, usize, usize)> = None;
+
+    for (y, (a, b)) in previous.pixels.rows().zip(frame.pixels.rows()).enumerate() {
+        let Some(first) = a.iter().zip(b).position(|(a, b)| a != b) else {
+            continue;
+        };
+        let last = a.iter().zip(b).rposition(|(a, b)| a != b).unwrap();
+
+        let (left, top, right, _) = rectangle.unwrap_or((first, y, last + 1, y + 1));
+        rectangle = Some((left.min(first), top, right.max(last + 1), y + 1));
+    }
+
+    rectangle.unwrap_or((0, 0, 1, 1))
+}
+
+/// Compress palette indices with the variable-length LZW codes of GIF.
+fn lzw(indices: impl IntoIterator<Item = u8>, min_code_size: u32) -> Vec<u8> {
+    let clear = 1u16 << min_code_size;
+    let end = clear + 1;
+
+    let mut bits = BitWriter::default();
+    let mut code_size = min_code_size + 1;
+    let mut next = end + 1;
+    let mut table: FastMap<(u16, u8), u16> = FastMap::default();
+
+    bits.write(clear.into(), code_size);
+
+    let mut indices = indices.into_iter();
+    let Some(first) = indices.next() else {
+        bits.write(end.into(), code_size);
+        return bits.finish();
+    };
+    let mut prefix = u16::from(first);
+
+    for index in indices {
+        if let Some(&code) = table.get(&(prefix, index)) {
+            prefix = code;
+            continue;
+        }
+
+        bits.write(prefix.into(), code_size);
+        if next == MAX_CODES {
+            bits.write(clear.into(), code_size);
+            table.clear();
+            next = end + 1;
+            code_size = min_code_size + 1;
+        } else {
+            if next >= 1 << code_size {
+                code_size += 1;
+            }
+            table.insert((prefix, index), next);
+            next += 1;
+        }
+        prefix = index.into();
+    }
+
+    bits.write(prefix.into(), code_size);
+    bits.write(end.into(), code_size);
+    bits.finish()
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{changed_rectangle, encode, lzw};
+    use crate::grid::Point;
+    use crate::image::{Image, Rgb};
+    use crate::rng::Rng;
+
+    /// Decodes GIF LZW codes back into indices.
+    fn decode(bytes: &[u8], min_code_size: u32) -> Vec<u8> {
+        let clear = 1usize << min_code_size;
+        let mut position = 0;
+        let mut read = |count: u32| {
+            (0..count).fold(0usize, |value, i| {
+                let bit = (bytes[position / 8] >> (position % 8)) & 1;
+                position += 1;
+                value | usize::from(bit) << i
+            })
+        };
+
+        let mut out = vec![];
+        let mut table: Vec<Vec<u8>> = vec![];
+        let mut code_size = min_code_size + 1;
+        let mut previous: Option<Vec<u8>> = None;
+
+        loop {
+            let code = read(code_size);
+            if code == clear {
+                table = (0..clear).map(|i| vec![i as u8]).collect();
+                table.extend([vec![], vec![]]);
+                code_size = min_code_size + 1;
+                previous = None;
+                continue;
+            }
+            if code == clear + 1 {
+                return out;
+            }
+
+            let entry = match (table.get(code), &previous) {
+                (Some(entry), _) => entry.clone(),
+                (None, Some(previous)) => [&previous[..], &previous[..1]].concat(),
+                (None, None) => panic!("unknown code {code}"),
+            };
+            out.extend(&entry);
+
+            if let Some(previous) = previous {
+                if table.len() < 4096 {
+                    table.push([&previous[..], &entry[..1]].concat());
+                }
+            }
+            if table.len() == 1 << code_size && code_size < 12 {
+                code_size += 1;
+            }
+            previous = Some(entry);
+        }
+    }
+
+    #[test]
+    fn round_trips_lzw() {
+        let mut rng = Rng::new(3);
+        let inputs: Vec<Vec<u8>> = vec![
+            vec![],
+            vec![1],
+            vec![0; 10_000],
+            (0..50_000).map(|_| rng.range(0u8..4)).collect(),
+            (0..50_000).map(|_| rng.range(0..=u8::MAX)).collect(),
+        ];
+
+        for input in inputs {
+            let min_code_size = if input.iter().any(|&i| i >= 4) { 8 } else { 2 };
+            assert_eq!(
+                decode(&lzw(input.iter().copied(), min_code_size), min_code_size),
+                input,
+                "{} indices",
+                input.len()
+            );
+        }
+    }
+
+    #[test]
+    fn only_stores_changes() {
+        let first = Image::new(8, 6, Rgb::BLACK);
+        let mut second = first.clone();
+        second.set(Point::new(2, 1), Rgb::WHITE);
+        second.set(Point::new(5, 3), Rgb::WHITE);
+
+        assert_eq!(changed_rectangle(&first, &second), (2, 1, 6, 4));
+        assert_eq!(changed_rectangle(&first, &first), (0, 0, 1, 1));
+    }
+
+    #[test]
+    fn writes_header_and_trailer() {
+        let mut frame = Image::new(4, 3, Rgb::BLACK);
+        let first = frame.clone();
+        frame.set(Point::new(1, 1), Rgb::new(255, 0, 0));
+
+        let gif = encode(&[first, frame], 5).unwrap();
+        assert_eq!(&gif[..6], b"GIF89a");
+        assert_eq!(gif[6..10], [4, 0, 3, 0]);
+        // Two colours fit into the smallest table.
+        assert_eq!(gif[10], 0xf0);
+        assert_eq!(gif[13..19], [0, 0, 0, 255, 0, 0]);
+        assert_eq!(gif.last(), Some(&0x3b));
+    }
+}
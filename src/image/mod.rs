@@ -0,0 +1,299 @@
+//! Images of grids, written as PNG files and animated GIFs without external libraries.
+//!
+//! An [`Image`] has one pixel per cell of a grid, coloured by a mapping from cells to colours.
+//! Images are usually [`scaled`](Image::scaled) up before saving, so that the cells are visible.
+//!
+//! ```
+//! use advent_of_code::grid::Grid;
+//! use advent_of_code::image::{Image, Rgb};
+//!
+//! let map = Grid::parse("..#\n#..", |c| c == '#');
+//! let image = Image::from_grid(&map, |&wall| if wall { Rgb::WHITE } else { Rgb::BLACK }).scaled(4);
+//! assert_eq!((image.width(), image.height()), (12, 8));
+//! assert!(image.to_png().starts_with(b"\x89PNG"));
+//! ```
+use std::error::Error;
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::grid::{Bounds, Grid, Point, SparseGrid};
+
+mod deflate;
+mod gif;
+mod png;
+
+/// A colour with 8 bits per channel.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Rgb {
+    pub const BLACK: Self = Self::new(0, 0, 0);
+    pub const WHITE: Self = Self::new(255, 255, 255);
+
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+}
+
+#[derive(Debug)]
+pub enum ImageError {
+    /// GIFs can only use 256 colours across all frames.
+    TooManyColors(usize),
+    /// The frames of an animation have different sizes.
+    SizeMismatch {
+        expected: (usize, usize),
+        found: (usize, usize),
+    },
+    /// An animation without frames.
+    NoFrames,
+    /// GIFs can be at most 65535 pixels wide and high.
+    TooLarge {
+        width: usize,
+        height: usize,
+    },
+    Io(io::Error),
+}
+
+impl Error for ImageError {}
+
+impl Display for ImageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImageError::TooManyColors(colors) => {
+                write!(f, "expected at most 256 colours, got {colors}")
+            }
+            ImageError::SizeMismatch { expected, found } => write!(
+                f,
+                "expected frames of {}x{} pixels, got {}x{}",
+                expected.0, expected.1, found.0, found.1
+            ),
+            ImageError::NoFrames => write!(f, "an animation needs at least one frame"),
+            ImageError::TooLarge { width, height } => write!(
+                f,
+                "expected at most {}x{} pixels, got {width}x{height}",
+                u16::MAX,
+                u16::MAX
+            ),
+            ImageError::Io(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl From<io::Error> for ImageError {
+    fn from(e: io::Error) -> Self {
+        ImageError::Io(e)
+    }
+}
+
+/// A rectangle of pixels.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Image {
+    pixels: Grid<Rgb>,
+}
+
+impl Image {
+    /// An image with every pixel set to `color`.
+    pub fn new(width: usize, height: usize, color: Rgb) -> Self {
+        Self {
+            pixels: Grid::new(width, height, color),
+        }
+    }
+
+    /// One pixel per cell of `grid`.
+    pub fn from_grid<T>(grid: &Grid<T>, color: impl FnMut(&T) -> Rgb) -> Self {
+        Self {
+            pixels: grid.map(color),
+        }
+    }
+
+    /// One pixel per point of `bounds`, unoccupied points are drawn in `empty`.
+    pub fn from_sparse<T>(
+        grid: &SparseGrid<T>,
+        bounds: Bounds,
+        empty: Rgb,
+        mut color: impl FnMut(&T) -> Rgb,
+    ) -> Self {
+        let pixels = bounds
+            .points()
+            .map(|point| grid.get(point).map_or(empty, &mut color))
+            .collect();
+        Self {
+            pixels: Grid::from_cells(bounds.width(), bounds.height(), pixels),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.pixels.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.pixels.height()
+    }
+
+    pub fn get(&self, point: Point) -> Option<Rgb> {
+        self.pixels.get(point).copied()
+    }
+
+    /// Set a pixel, points outside of the image are ignored.
+    pub fn set(&mut self, point: Point, color: Rgb) {
+        if let Some(pixel) = self.pixels.get_mut(point) {
+            *pixel = color;
+        }
+    }
+
+    /// The image with every pixel drawn as a square of `factor` by `factor` pixels.
+    #[must_use]
+    pub fn scaled(&self, factor: usize) -> Self {
+        let pixels = self
+            .pixels
+            .rows()
+            .flat_map(|row| {
+                let row: Vec<Rgb> = row
+                    .iter()
+                    .flat_map(|&pixel| std::iter::repeat_n(pixel, factor))
+                    .collect();
+                std::iter::repeat_n(row, factor).flatten()
+            })
+            .collect();
+        Self {
+            pixels: Grid::from_cells(self.width() * factor, self.height() * factor, pixels),
+        }
+    }
+
+    /// The image encoded as a PNG file.
+    pub fn to_png(&self) -> Vec<u8> {
+        png::encode(self)
+    }
+
+    /// Write the image as a PNG file, creating its directory if needed.
+    pub fn save_png(&self, path: impl AsRef<Path>) -> Result<(), ImageError> {
+        write(path.as_ref(), &self.to_png())
+    }
+}
+
+/// Frames of the same size, shown one after another in a loop.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Animation {
+    frames: Vec<Image>,
+    delay: Duration,
+}
+
+impl Animation {
+    /// An animation that shows every frame for `delay`, rounded to hundredths of a second.
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            frames: vec![],
+            delay,
+        }
+    }
+
+    pub fn push(&mut self, frame: Image) {
+        self.frames.push(frame);
+    }
+
+    pub fn frames(&self) -> &[Image] {
+        &self.frames
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// The animation encoded as a looping GIF.
+    pub fn to_gif(&self) -> Result<Vec<u8>, ImageError> {
+        // NOTE: most viewers show delays below two hundredths of a second much slower.
+        let delay = (self.delay.as_millis() / 10).clamp(2, u128::from(u16::MAX)) as u16;
+        gif::encode(&self.frames, delay)
+    }
+
+    /// Write the animation as a GIF file, creating its directory if needed.
+    pub fn save_gif(&self, path: impl AsRef<Path>) -> Result<(), ImageError> {
+        write(path.as_ref(), &self.to_gif()?)
+    }
+}
+
+fn write(path: &Path, bytes: &[u8]) -> Result<(), ImageError> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, bytes)?;
+    Ok(())
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Animation, Image, ImageError, Rgb};
+    use crate::grid::{Bounds, Grid, Point, SparseGrid};
+    use std::time::Duration;
+
+    #[test]
+    fn maps_cells_to_pixels() {
+        let grid = Grid::parse("ab\nba", |c| c == 'a');
+        let image = Image::from_grid(&grid, |&a| if a { Rgb::WHITE } else { Rgb::BLACK });
+        assert_eq!(image.get(Point::new(0, 0)), Some(Rgb::WHITE));
+        assert_eq!(image.get(Point::new(1, 0)), Some(Rgb::BLACK));
+
+        let scaled = image.scaled(3);
+        assert_eq!((scaled.width(), scaled.height()), (6, 6));
+        assert_eq!(scaled.get(Point::new(2, 2)), Some(Rgb::WHITE));
+        assert_eq!(scaled.get(Point::new(3, 2)), Some(Rgb::BLACK));
+        assert_eq!(scaled.get(Point::new(3, 3)), Some(Rgb::WHITE));
+
+        let sparse: SparseGrid<()> = [(Point::new(1, 1), ())].into_iter().collect();
+        let image = Image::from_sparse(&sparse, Bounds::from_size(2, 2), Rgb::BLACK, |()| {
+            Rgb::WHITE
+        });
+        assert_eq!(image.get(Point::new(1, 1)), Some(Rgb::WHITE));
+        assert_eq!(image.get(Point::new(0, 1)), Some(Rgb::BLACK));
+    }
+
+    #[test]
+    fn rejects_invalid_animations() {
+        let mut animation = Animation::new(Duration::from_millis(50));
+        assert!(matches!(animation.to_gif(), Err(ImageError::NoFrames)));
+
+        animation.push(Image::new(2, 2, Rgb::BLACK));
+        animation.push(Image::new(3, 2, Rgb::BLACK));
+        assert!(matches!(
+            animation.to_gif(),
+            Err(ImageError::SizeMismatch {
+                expected: (2, 2),
+                found: (3, 2)
+            })
+        ));
+
+        let mut image = Image::new(20, 20, Rgb::BLACK);
+        for point in Bounds::from_size(20, 20).points() {
+            image.set(point, Rgb::new(point.x as u8, point.y as u8, 0));
+        }
+        let mut animation = Animation::new(Duration::from_millis(50));
+        animation.push(image);
+        assert!(matches!(
+            animation.to_gif(),
+            Err(ImageError::TooManyColors(400))
+        ));
+
+        let mut animation = Animation::new(Duration::from_millis(50));
+        animation.push(Image::new(70_000, 1, Rgb::BLACK));
+        assert!(matches!(
+            animation.to_gif(),
+            Err(ImageError::TooLarge {
+                width: 70_000,
+                height: 1
+            })
+        ));
+    }
+}
@@ -0,0 +1,97 @@
+//! PNG encoding of 8-bit RGB images.
+use super::{deflate, Image};
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+const CRC_TABLE: [u32; 256] = crc_table();
+
+const fn crc_table() -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut n = 0;
+    while n < 256 {
+        let mut c = n as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 == 1 {
+                0xedb8_8320 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+            k += 1;
+        }
+        table[n] = c;
+        n += 1;
+    }
+    table
+}
+
+fn crc32<'a>(bytes: impl IntoIterator<Item = &'a u8>) -> u32 {
+    !bytes.into_iter().fold(!0, |crc, &byte| {
+        CRC_TABLE[((crc ^ u32::from(byte)) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+pub(super) fn encode(image: &Image) -> Vec<u8> {
+    let mut out = SIGNATURE.to_vec();
+
+    let mut header = Vec::with_capacity(13);
+    header.extend((image.width() as u32).to_be_bytes());
+    header.extend((image.height() as u32).to_be_bytes());
+    // NOTE: 8 bits per channel, RGB, deflate, no filtering method extensions, no interlacing.
+    header.extend([8, 2, 0, 0, 0]);
+    write_chunk(&mut out, b"IHDR", &header);
+
+    let mut raw = Vec::with_capacity((3 * image.width() + 1) * image.height());
+    for row in image.pixels.rows() {
+        raw.push(0); // filter type of the row: none
+        for pixel in row {
+            raw.extend([pixel.r, pixel.g, pixel.b]);
+        }
+    }
+    write_chunk(&mut out, b"IDAT", &deflate::zlib(&raw));
+    write_chunk(&mut out, b"IEND", &[]);
+
+    out
+}
+
+fn write_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend((data.len() as u32).to_be_bytes());
+    out.extend(kind);
+    out.extend(data);
+    out.extend(crc32(kind.iter().chain(data)).to_be_bytes());
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{crc32, encode, SIGNATURE};
+    use crate::image::{Image, Rgb};
+
+    #[test]
+    fn computes_crc32() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn writes_chunks() {
+        let png = encode(&Image::new(3, 2, Rgb::new(1, 2, 3)));
+        assert_eq!(png[..8], SIGNATURE);
+
+        let mut rest = &png[8..];
+        let mut kinds = vec![];
+        while !rest.is_empty() {
+            let len = u32::from_be_bytes(rest[..4].try_into().unwrap()) as usize;
+            let (chunk, crc) = rest[4..].split_at(4 + len);
+            assert_eq!(crc32(chunk).to_be_bytes(), crc[..4]);
+            kinds.push(String::from_utf8(chunk[..4].to_vec()).unwrap());
+
+            if &chunk[..4] == b"IHDR" {
+                assert_eq!(chunk[4..], [0, 0, 0, 3, 0, 0, 0, 2, 8, 2, 0, 0, 0]);
+            }
+            rest = &crc[4..];
+        }
+        assert_eq!(kinds, ["IHDR", "IDAT", "IEND"]);
+    }
+}
@@ -1,3 +1,19 @@
+pub mod allocations;
+pub mod combinatorics;
+pub mod counter;
+pub mod ds;
+pub mod expr;
+pub mod grid;
+pub mod hash;
+pub mod image;
+pub mod math;
+pub mod memo;
+pub mod ocr;
+pub mod rng;
+pub mod scanner;
+pub mod sequences;
 pub mod template;
+pub mod visual;
+pub mod vm;
 
 // Use this file to add helper functions and additional modules.
@@ -1,4 +1,4 @@
-use advent_of_code::template::commands::{all, download, read, scaffold, solve, time};
+use advent_of_code::template::commands::{all, download, read, scaffold, solve, stress, time};
 use args::{parse, AppArguments};
 
 #[cfg(feature = "today")]
@@ -7,7 +7,8 @@ use advent_of_code::template::Day;
 use std::process;
 
 mod args {
-    use advent_of_code::template::Day;
+    use advent_of_code::template::{differential, stress, Day, ExportFormat};
+    use advent_of_code::visual;
     use std::process;
 
     pub enum AppArguments {
@@ -27,6 +28,14 @@ mod args {
             release: bool,
             dhat: bool,
             submit: Option<u8>,
+            time: bool,
+            trace: Option<String>,
+            differential: Option<differential::Config>,
+            visual: Option<visual::Config>,
+        },
+        Stress {
+            day: Day,
+            config: stress::Config,
         },
         All {
             release: bool,
@@ -35,6 +44,9 @@ mod args {
             all: bool,
             day: Option<Day>,
             store: bool,
+            memory: bool,
+            export: Option<ExportFormat>,
+            out: Option<String>,
         },
         #[cfg(feature = "today")]
         Today,
@@ -50,11 +62,17 @@ mod args {
             Some("time") => {
                 let all = args.contains("--all");
                 let store = args.contains("--store");
+                let memory = args.contains("--memory");
+                let export = args.opt_value_from_str("--export")?;
+                let out = args.opt_value_from_str("--out")?;
 
                 AppArguments::Time {
                     all,
                     day: args.opt_free_from_str()?,
                     store,
+                    memory,
+                    export,
+                    out,
                 }
             }
             Some("download") => AppArguments::Download {
@@ -68,11 +86,55 @@ mod args {
                 download: args.contains("--download"),
                 overwrite: args.contains("--overwrite"),
             },
-            Some("solve") => AppArguments::Solve {
+            Some("solve") => {
+                let day = args.free_from_str()?;
+                let release = args.contains("--release");
+                let submit = args.opt_value_from_str("--submit")?;
+                let dhat = args.contains("--dhat");
+                let time = args.contains("--time");
+                let trace = args.opt_value_from_str("--trace")?;
+
+                let differential = if args.contains("--differential") {
+                    Some(differential::Config::from_args(&mut args)?)
+                } else {
+                    None
+                };
+
+                let terminal = args.contains("--visualize");
+                let render = args.contains("--render");
+                let visual = if terminal || render {
+                    if time || dhat {
+                        eprintln!(
+                            "--visualize and --render can not be combined with --time or --dhat."
+                        );
+                        process::exit(1);
+                    }
+                    let defaults = visual::Config::default();
+                    Some(visual::Config {
+                        terminal,
+                        render,
+                        speed: args
+                            .opt_value_from_str("--speed")?
+                            .unwrap_or(defaults.speed),
+                    })
+                } else {
+                    None
+                };
+
+                AppArguments::Solve {
+                    day,
+                    release,
+                    submit,
+                    dhat,
+                    time,
+                    trace,
+                    differential,
+                    visual,
+                }
+            }
+            Some("stress") => AppArguments::Stress {
                 day: args.free_from_str()?,
-                release: args.contains("--release"),
-                submit: args.opt_value_from_str("--submit")?,
-                dhat: args.contains("--dhat"),
+                config: stress::Config::from_args(&mut args)?,
             },
             #[cfg(feature = "today")]
             Some("today") => AppArguments::Today,
@@ -103,7 +165,14 @@ fn main() {
         }
         Ok(args) => match args {
             AppArguments::All { release } => all::handle(release),
-            AppArguments::Time { day, all, store } => time::handle(day, all, store),
+            AppArguments::Time {
+                day,
+                all,
+                store,
+                memory,
+                export,
+                out,
+            } => time::handle(day, all, store, memory, export, out),
             AppArguments::Download { day } => download::handle(day),
             AppArguments::Read { day } => read::handle(day),
             AppArguments::Scaffold {
@@ -121,7 +190,21 @@ fn main() {
                 release,
                 dhat,
                 submit,
-            } => solve::handle(day, release, dhat, submit),
+                time,
+                trace,
+                differential,
+                visual,
+            } => solve::handle(
+                day,
+                release,
+                dhat,
+                submit,
+                time,
+                trace,
+                differential,
+                visual,
+            ),
+            AppArguments::Stress { day, config } => stress::handle(day, config),
             #[cfg(feature = "today")]
             AppArguments::Today => {
                 match Day::today() {
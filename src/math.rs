@@ -0,0 +1,411 @@
+//! Number theory helpers that puzzles routinely need.
+//!
+//! All functions are generic over the primitive integer types through the [`Integer`] trait.
+//! Functions that can overflow come in two variants: one that panics on overflow and a `checked_` one returning `None`.
+//! Modular arithmetic does not overflow, products are computed in a wider type where one exists.
+use std::fmt::Debug;
+use std::ops::{Add, Div, Mul, Rem, Sub};
+
+/// A primitive integer type.
+pub trait Integer:
+    Copy
+    + Ord
+    + Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+    const TWO: Self;
+    const TEN: Self;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn checked_pow(self, exp: u32) -> Option<Self>;
+    /// The least non-negative remainder of `self` modulo `rhs`.
+    fn rem_euclid(self, rhs: Self) -> Self;
+    /// The absolute value, which is `self` for unsigned types.
+    ///
+    /// # Panics
+    /// Panics if the result overflows, which is the case for the `MIN` of a signed type.
+    fn abs(self) -> Self;
+    /// The integer square root, or `None` if `self` is negative.
+    fn checked_isqrt(self) -> Option<Self>;
+
+    /// `self * rhs mod modulus` for `self` and `rhs` in `0..modulus`.
+    fn mul_mod(self, rhs: Self, modulus: Self) -> Self {
+        // NOTE: double-and-add, for types without a wider type to multiply in.
+        let (mut a, mut b) = (self, rhs);
+        let mut result = Self::ZERO;
+        while b > Self::ZERO {
+            if b % Self::TWO == Self::ONE {
+                result = add_mod(result, a, modulus);
+            }
+            a = add_mod(a, a, modulus);
+            b = b / Self::TWO;
+        }
+        result
+    }
+}
+
+macro_rules! impl_integer {
+    ($t:ty $(=> $wide:ty)?) => {
+        impl Integer for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+            const TWO: Self = 2;
+            const TEN: Self = 10;
+
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_add(self, rhs)
+            }
+
+            fn checked_sub(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_sub(self, rhs)
+            }
+
+            fn checked_mul(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_mul(self, rhs)
+            }
+
+            fn checked_pow(self, exp: u32) -> Option<Self> {
+                <$t>::checked_pow(self, exp)
+            }
+
+            fn rem_euclid(self, rhs: Self) -> Self {
+                <$t>::rem_euclid(self, rhs)
+            }
+
+            #[allow(unused_comparisons)]
+            fn abs(self) -> Self {
+                if self < 0 {
+                    Self::ZERO
+                        .checked_sub(self)
+                        .expect("absolute value overflowed")
+                } else {
+                    self
+                }
+            }
+
+            #[allow(unused_comparisons)]
+            fn checked_isqrt(self) -> Option<Self> {
+                (self >= 0).then(|| self.isqrt())
+            }
+
+            $(
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss, clippy::cast_possible_wrap)]
+                fn mul_mod(self, rhs: Self, modulus: Self) -> Self {
+                    (self as $wide * rhs as $wide % modulus as $wide) as $t
+                }
+            )?
+        }
+    };
+}
+
+impl_integer!(u32 => u64);
+impl_integer!(u64 => u128);
+impl_integer!(usize => u128);
+impl_integer!(u128);
+impl_integer!(i32 => i64);
+impl_integer!(i64 => i128);
+impl_integer!(isize => i128);
+impl_integer!(i128);
+
+/* -------------------------------------------------------------------------- */
+
+/// The greatest common divisor of `a` and `b`, which is never negative. `gcd(0, 0)` is `0`.
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != T::ZERO {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// The least common multiple of `a` and `b`, which is never negative.
+///
+/// # Panics
+/// Panics if the result overflows.
+pub fn lcm<T: Integer>(a: T, b: T) -> T {
+    checked_lcm(a, b).expect("least common multiple overflowed")
+}
+
+/// The least common multiple of `a` and `b`, or `None` if it overflows.
+pub fn checked_lcm<T: Integer>(a: T, b: T) -> Option<T> {
+    if a == T::ZERO || b == T::ZERO {
+        return Some(T::ZERO);
+    }
+    (a.abs() / gcd(a, b)).checked_mul(b.abs())
+}
+
+/// `base ^ exp mod modulus`, in `0..modulus`.
+///
+/// # Panics
+/// Panics if `modulus` is not positive.
+pub fn mod_pow<T: Integer>(base: T, mut exp: u64, modulus: T) -> T {
+    assert!(modulus > T::ZERO, "modulus must be positive");
+
+    let mut base = base.rem_euclid(modulus);
+    let mut result = T::ONE % modulus;
+
+    while exp > 0 {
+        if exp % 2 == 1 {
+            result = result.mul_mod(base, modulus);
+        }
+        base = base.mul_mod(base, modulus);
+        exp /= 2;
+    }
+
+    result
+}
+
+/// The inverse `x` of `a` modulo `modulus`, i.e. `a * x mod modulus == 1`, in `0..modulus`.
+/// Returns `None` if `a` and `modulus` are not coprime or `modulus` is not positive.
+pub fn mod_inv<T: Integer>(a: T, modulus: T) -> Option<T> {
+    if modulus <= T::ZERO {
+        return None;
+    }
+
+    // NOTE: extended euclid, keeping the coefficient of `a` reduced modulo `modulus` so it stays non-negative.
+    let (mut r0, mut r1) = (modulus, a.rem_euclid(modulus));
+    let (mut s0, mut s1) = (T::ZERO, T::ONE % modulus);
+
+    while r1 != T::ZERO {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (s0, s1) = (s1, sub_mod(s0, (q % modulus).mul_mod(s1, modulus), modulus));
+    }
+
+    (r0 == T::ONE).then_some(s0)
+}
+
+/// Solve a system of congruences `x ≡ residue (mod modulus)` with the chinese remainder theorem.
+/// The moduli don't need to be coprime.
+///
+/// Returns the smallest non-negative solution `x` together with the modulus of all solutions, i.e. the lcm of all moduli.
+/// Returns `None` if there is no solution, a modulus is not positive or the lcm overflows.
+pub fn crt<T: Integer>(congruences: &[(T, T)]) -> Option<(T, T)> {
+    let mut x = T::ZERO;
+    let mut m = T::ONE;
+
+    for &(residue, modulus) in congruences {
+        if modulus <= T::ZERO {
+            return None;
+        }
+
+        // x + m * t ≡ residue (mod modulus) <=> m * t ≡ residue - x (mod modulus)
+        let g = gcd(m, modulus);
+        let diff = sub_mod(residue.rem_euclid(modulus), x % modulus, modulus);
+        if diff % g != T::ZERO {
+            return None;
+        }
+
+        let reduced = modulus / g;
+        let t = (diff / g).mul_mod(mod_inv(m / g, reduced)?, reduced);
+
+        let lcm = (m / g).checked_mul(modulus)?;
+        x = add_mod(x, m * t, lcm);
+        m = lcm;
+    }
+
+    Some((x, m))
+}
+
+/// The integer square root of `n`, i.e. the largest `r` with `r * r <= n`.
+///
+/// # Panics
+/// Panics if `n` is negative.
+pub fn isqrt<T: Integer>(n: T) -> T {
+    n.checked_isqrt()
+        .expect("cannot take the square root of a negative number")
+}
+
+/// The number of decimal digits of `n`, ignoring its sign. `0` has one digit.
+pub fn digits<T: Integer>(n: T) -> u32 {
+    let mut n = n / T::TEN;
+    let mut count = 1;
+    while n != T::ZERO {
+        n = n / T::TEN;
+        count += 1;
+    }
+    count
+}
+
+/// `10 ^ exp`.
+///
+/// # Panics
+/// Panics if the result overflows.
+pub fn pow10<T: Integer>(exp: u32) -> T {
+    checked_pow10(exp).expect("power of ten overflowed")
+}
+
+/// `10 ^ exp`, or `None` if it overflows.
+pub fn checked_pow10<T: Integer>(exp: u32) -> Option<T> {
+    T::TEN.checked_pow(exp)
+}
+
+/// The decimal concatenation of `a` and `b`, e.g. `concat(12, 345) == 12345`.
+///
+/// # Panics
+/// Panics if the result overflows.
+pub fn concat<T: Integer>(a: T, b: T) -> T {
+    checked_concat(a, b).expect("concatenation overflowed")
+}
+
+/// The decimal concatenation of non-negative `a` and `b`, or `None` if it overflows.
+pub fn checked_concat<T: Integer>(a: T, b: T) -> Option<T> {
+    a.checked_mul(checked_pow10(digits(b))?)?.checked_add(b)
+}
+
+/// The inverse of [`concat`]: the `prefix` with `concat(prefix, suffix) == n`, if the digits of `n` end with `suffix`.
+/// For non-negative `n` and `suffix`.
+pub fn split_concat<T: Integer>(n: T, suffix: T) -> Option<T> {
+    if n < suffix {
+        return None;
+    }
+    let Some(shift) = checked_pow10::<T>(digits(suffix)) else {
+        // NOTE: `suffix` has as many digits as the largest value, so only `n == suffix` can end with it.
+        return (n == suffix).then_some(T::ZERO);
+    };
+    (n % shift == suffix).then(|| n / shift)
+}
+
+/// `a + b mod modulus` for `a` and `b` in `0..modulus`.
+fn add_mod<T: Integer>(a: T, b: T, modulus: T) -> T {
+    if a >= modulus - b {
+        a - (modulus - b)
+    } else {
+        a + b
+    }
+}
+
+/// `a - b mod modulus` for `a` and `b` in `0..modulus`.
+fn sub_mod<T: Integer>(a: T, b: T, modulus: T) -> T {
+    if a >= b {
+        a - b
+    } else {
+        modulus - (b - a)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{
+        checked_concat, checked_lcm, checked_pow10, concat, crt, digits, gcd, isqrt, lcm, mod_inv,
+        mod_pow, pow10, split_concat, Integer,
+    };
+
+    #[test]
+    fn computes_gcd_and_lcm() {
+        assert_eq!(gcd(12_u64, 18), 6);
+        assert_eq!(gcd(-12_i64, 18), 6);
+        assert_eq!(gcd(0_u32, 7), 7);
+        assert_eq!(gcd(0_u32, 0), 0);
+        assert_eq!(lcm(4_u64, 6), 12);
+        assert_eq!(lcm(-4_i64, 6), 12);
+        assert_eq!(lcm(0_u64, 6), 0);
+        assert_eq!(checked_lcm(u64::MAX, u64::MAX - 1), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_overflowing_lcm() {
+        lcm(u32::MAX, u32::MAX - 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_overflowing_abs() {
+        Integer::abs(i32::MIN);
+    }
+
+    #[test]
+    fn computes_mod_pow() {
+        assert_eq!(mod_pow(2_u64, 10, 1000), 24);
+        assert_eq!(mod_pow(3_u32, 0, 7), 1);
+        assert_eq!(mod_pow(5_u64, 3, 1), 0);
+        assert_eq!(mod_pow(-2_i64, 3, 5), 2);
+        // NOTE: products of values near `u64::MAX` overflow without a wider type.
+        assert_eq!(mod_pow(u64::MAX - 1, 2, u64::MAX), 1);
+        assert_eq!(mod_pow(u128::MAX - 1, 2, u128::MAX), 1);
+    }
+
+    #[test]
+    fn computes_mod_inv() {
+        assert_eq!(mod_inv(3_u64, 7), Some(5));
+        assert_eq!(mod_inv(-3_i64, 7), Some(2));
+        assert_eq!(mod_inv(2_u64, 4), None);
+        assert_eq!(mod_inv(5_u64, 1), Some(0));
+        assert_eq!(mod_inv(5_i64, 0), None);
+
+        let p = 1_000_000_007_u128;
+        let inverse = mod_inv(123_456_789, p).unwrap();
+        assert_eq!(123_456_789.mul_mod(inverse, p), 1);
+    }
+
+    #[test]
+    fn solves_congruences() {
+        assert_eq!(crt(&[(2_u64, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(-1_i64, 4), (1, 3)]), Some((7, 12)));
+        assert_eq!(crt::<u64>(&[]), Some((0, 1)));
+    }
+
+    #[test]
+    fn solves_congruences_with_common_factors() {
+        assert_eq!(crt(&[(3_u64, 4), (5, 6)]), Some((11, 12)));
+        assert_eq!(crt(&[(1_u64, 4), (2, 6)]), None);
+        assert_eq!(crt(&[(1_u64, 0)]), None);
+    }
+
+    #[test]
+    fn detects_overflowing_congruences() {
+        let primes = [(1_u32, 65_521), (2, 65_519), (3, 65_497)];
+        assert_eq!(crt(&primes), None);
+        assert!(crt(&primes.map(|(r, m)| (u64::from(r), u64::from(m)))).is_some());
+    }
+
+    #[test]
+    fn computes_isqrt() {
+        assert_eq!(isqrt(0_u64), 0);
+        assert_eq!(isqrt(24_u64), 4);
+        assert_eq!(isqrt(25_i64), 5);
+        assert_eq!(isqrt(u128::MAX), u128::from(u64::MAX));
+        assert_eq!((-1_i64).checked_isqrt(), None);
+    }
+
+    #[test]
+    fn counts_digits() {
+        assert_eq!(digits(0_u64), 1);
+        assert_eq!(digits(9_u64), 1);
+        assert_eq!(digits(10_u64), 2);
+        assert_eq!(digits(-123_i64), 3);
+        assert_eq!(digits(u64::MAX), 20);
+    }
+
+    #[test]
+    fn computes_powers_of_ten() {
+        assert_eq!(pow10::<u64>(0), 1);
+        assert_eq!(pow10::<u64>(19), 10_000_000_000_000_000_000);
+        assert_eq!(checked_pow10::<u64>(20), None);
+        assert_eq!(checked_pow10::<u128>(20), Some(100_000_000_000_000_000_000));
+    }
+
+    #[test]
+    fn concatenates() {
+        assert_eq!(concat(12_u64, 345), 12345);
+        assert_eq!(concat(15_u64, 0), 150);
+        assert_eq!(checked_concat(u64::MAX, 1), None);
+        assert_eq!(split_concat(12345_u64, 345), Some(12));
+        assert_eq!(split_concat(345_u64, 345), Some(0));
+        assert_eq!(split_concat(12345_u64, 45), Some(123));
+        assert_eq!(split_concat(12345_u64, 44), None);
+        assert_eq!(split_concat(45_u64, 345), None);
+        assert_eq!(split_concat(u64::MAX, u64::MAX), Some(0));
+    }
+}
@@ -0,0 +1,184 @@
+//! Memoization of recursive functions.
+//!
+//! [`Memo`] caches results by key and is passed along explicitly, while [`memoize!`](crate::memoize)
+//! turns a function into a memoized one with a thread-local cache.
+//! When running with `--time`, lookups are counted and their hit rate is printed with the part's spans.
+//!
+//! ```
+//! use advent_of_code::memo::Memo;
+//!
+//! fn fibonacci(memo: &mut Memo<u64, u64>, n: u64) -> u64 {
+//!     if n < 2 {
+//!         return n;
+//!     }
+//!     memo.call(n - 1, fibonacci) + memo.call(n - 2, fibonacci)
+//! }
+//!
+//! let mut memo = Memo::new("fibonacci");
+//! assert_eq!(memo.call(90, fibonacci), 2_880_067_194_370_816_120);
+//! ```
+use std::hash::Hash;
+
+use crate::hash::FastMap;
+use crate::template::spans;
+
+/// A cache of the results of a function by key.
+#[derive(Debug, Clone)]
+pub struct Memo<K, V> {
+    name: &'static str,
+    cache: FastMap<K, V>,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Memo<K, V> {
+    /// An empty cache, `name` identifies its hit rate in timings.
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            cache: FastMap::default(),
+        }
+    }
+
+    /// The cached result for `key`.
+    pub fn get(&self, key: &K) -> Option<V> {
+        let value = self.cache.get(key).cloned();
+        if spans::is_collecting() {
+            spans::record_lookup(self.name, value.is_some());
+        }
+        value
+    }
+
+    pub fn insert(&mut self, key: K, value: V) {
+        self.cache.insert(key, value);
+    }
+
+    /// The result of `f(self, key)`, computed at most once per key.
+    /// `f` can call back into the memo for smaller keys.
+    pub fn call(&mut self, key: K, f: impl FnOnce(&mut Self, K) -> V) -> V {
+        if let Some(value) = self.get(&key) {
+            return value;
+        }
+        let value = f(self, key.clone());
+        self.insert(key, value.clone());
+        value
+    }
+
+    /// The result of `f()`, computed at most once per key.
+    pub fn get_or_insert_with(&mut self, key: K, f: impl FnOnce() -> V) -> V {
+        self.call(key, |_, _| f())
+    }
+
+    /// Forget all results, e.g. before solving another input.
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+
+    /// The number of cached results.
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+}
+
+/// Memoize a function with a thread-local cache, named by the identifier before the function.
+///
+/// The arguments form the key, so they have to implement `Clone`, `Eq` and `Hash`, and the result `Clone`.
+/// The cache lives as long as the thread, so clear it when the results depend on more than the arguments,
+/// e.g. at the start of a part with `CACHE.with_borrow_mut(Memo::clear)`.
+///
+/// ```
+/// use advent_of_code::memo::Memo;
+///
+/// advent_of_code::memoize! {
+///     PATHS: fn paths(x: u32, y: u32) -> u64 {
+///         if x == 0 || y == 0 { 1 } else { paths(x - 1, y) + paths(x, y - 1) }
+///     }
+/// }
+///
+/// assert_eq!(paths(16, 16), 601_080_390);
+/// PATHS.with_borrow_mut(Memo::clear);
+/// ```
+#[macro_export]
+macro_rules! memoize {
+    ($cache:ident: $vis:vis fn $name:ident($($arg:ident: $ty:ty),* $(,)?) -> $ret:ty $body:block) => {
+        ::std::thread_local! {
+            static $cache: ::std::cell::RefCell<$crate::memo::Memo<($($ty,)*), $ret>> =
+                ::std::cell::RefCell::new($crate::memo::Memo::new(stringify!($name)));
+        }
+
+        $vis fn $name($($arg: $ty),*) -> $ret {
+            let key = ($(::std::clone::Clone::clone(&$arg),)*);
+            if let Some(value) = $cache.with_borrow(|memo| memo.get(&key)) {
+                return value;
+            }
+            let value: $ret = $body;
+            $cache.with_borrow_mut(|memo| memo.insert(key, ::std::clone::Clone::clone(&value)));
+            value
+        }
+    };
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Memo;
+    use crate::template::spans;
+
+    fn fibonacci(memo: &mut Memo<u64, u64>, n: u64) -> u64 {
+        if n < 2 {
+            return n;
+        }
+        memo.call(n - 1, fibonacci) + memo.call(n - 2, fibonacci)
+    }
+
+    crate::memoize! {
+        CALLS: fn collatz_steps(n: u64) -> u32 {
+            match n {
+                1 => 0,
+                n if n % 2 == 0 => 1 + collatz_steps(n / 2),
+                n => 1 + collatz_steps(3 * n + 1),
+            }
+        }
+    }
+
+    #[test]
+    fn caches_recursive_results() {
+        let mut memo = Memo::new("fibonacci");
+        assert_eq!(memo.call(50, fibonacci), 12_586_269_025);
+        assert_eq!(memo.len(), 51);
+
+        memo.clear();
+        assert!(memo.is_empty());
+        assert_eq!(memo.get_or_insert_with(3, || 7), 7);
+        assert_eq!(memo.call(3, fibonacci), 7);
+    }
+
+    #[test]
+    fn memoizes_functions() {
+        assert_eq!(collatz_steps(27), 111);
+        let cached = CALLS.with_borrow(Memo::len);
+        assert_eq!(cached, 112);
+
+        assert_eq!(collatz_steps(54), 112);
+        assert_eq!(CALLS.with_borrow(Memo::len), cached + 1);
+
+        CALLS.with_borrow_mut(Memo::clear);
+        assert!(CALLS.with_borrow(Memo::is_empty));
+    }
+
+    #[test]
+    fn reports_hit_rate() {
+        let (_, spans) = spans::collect(|| {
+            crate::span!("solve");
+            let mut memo = Memo::new("fibonacci");
+            memo.call(10, fibonacci)
+        });
+
+        assert_eq!(spans[1].name, "fibonacci");
+        assert_eq!(spans[1].depth, 1);
+        assert_eq!((spans[1].calls, spans[1].hits), (19, Some(8)));
+    }
+}
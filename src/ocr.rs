@@ -0,0 +1,290 @@
+//! Recognition of the capital letters that some puzzles draw as ASCII art.
+//!
+//! Both fonts used by Advent of Code are supported: the 4x6 font and the larger 6x10 font.
+//! Lit pixels are drawn with `#` (or `█`), unlit pixels with `.` (or a space).
+//!
+//! Solutions can return [`Letters`] as answer. The runner displays the art and submits the decoded letters.
+use std::error::Error;
+use std::fmt::Display;
+
+/// Glyphs of the 4x6 font.
+const FONT_6: [(char, [&str; 6]); 18] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', ["###", ".#.", ".#.", ".#.", ".#.", "###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+/// Glyphs of the 6x10 font.
+#[rustfmt::skip]
+const FONT_10: [(char, [&str; 10]); 15] = [
+    ('A', ["..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#"]),
+    ('B', ["#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#", "#....#", "#####."]),
+    ('C', [".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#....#", ".####."]),
+    ('E', ["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('F', ["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('G', [".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#", "#...##", ".###.#"]),
+    ('H', ["#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#", "#....#"]),
+    ('J', ["...###", "....#.", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.", "#...#.", ".###.."]),
+    ('K', ["#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..", "#...#.", "#....#"]),
+    ('L', ["#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('N', ["#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##", "#...##", "#....#"]),
+    ('P', ["#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('R', ["#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.", "#....#", "#....#"]),
+    ('X', ["#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.", "#....#", "#....#"]),
+    ('Z', ["######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....", "#.....", "######"]),
+];
+
+/// An error which can be returned when recognizing letters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OcrError {
+    /// The art is neither 6 nor 10 pixels high.
+    UnsupportedHeight(usize),
+    /// A glyph that is not part of the font, with its position among the glyphs.
+    UnknownGlyph { index: usize, glyph: String },
+}
+
+impl Error for OcrError {}
+
+impl Display for OcrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OcrError::UnsupportedHeight(height) => {
+                write!(
+                    f,
+                    "expected letters to be 6 or 10 pixels high, got {height}"
+                )
+            }
+            OcrError::UnknownGlyph { index, glyph } => {
+                write!(f, "unknown glyph at position {index}:\n{glyph}")
+            }
+        }
+    }
+}
+
+/// Recognize the letters drawn in `art`.
+/// Blank lines and rows of unlit pixels around the art are ignored, letters are separated by blank columns.
+/// Rows shorter than the longest row are treated as if padded with unlit pixels.
+pub fn recognize(art: &str) -> Result<String, OcrError> {
+    let is_lit = |row: &Vec<bool>| row.contains(&true);
+
+    let mut rows: Vec<Vec<bool>> = art
+        .lines()
+        .map(|line| line.chars().map(|c| c == '#' || c == '█').collect())
+        .skip_while(|row| !is_lit(row))
+        .collect();
+
+    let height = rows.iter().rposition(is_lit).map_or(0, |i| i + 1);
+    rows.truncate(height);
+
+    if height != 6 && height != 10 {
+        return Err(OcrError::UnsupportedHeight(height));
+    }
+
+    let width = rows.iter().map(Vec::len).max().unwrap_or_default();
+    for row in &mut rows {
+        row.resize(width, false);
+    }
+
+    let is_blank_column = |x: usize| rows.iter().all(|row| !row[x]);
+
+    let mut letters = String::new();
+    let mut x = 0;
+
+    while x < width {
+        if is_blank_column(x) {
+            x += 1;
+            continue;
+        }
+
+        let start = x;
+        while x < width && !is_blank_column(x) {
+            x += 1;
+        }
+
+        let glyph: Vec<&[bool]> = rows.iter().map(|row| &row[start..x]).collect();
+        let letter = if height == 6 {
+            lookup(&FONT_6, &glyph)
+        } else {
+            lookup(&FONT_10, &glyph)
+        };
+
+        match letter {
+            Some(letter) => letters.push(letter),
+            None => {
+                return Err(OcrError::UnknownGlyph {
+                    index: letters.len(),
+                    glyph: render(&glyph),
+                })
+            }
+        }
+    }
+
+    Ok(letters)
+}
+
+fn lookup<const N: usize>(font: &[(char, [&str; N])], glyph: &[&[bool]]) -> Option<char> {
+    font.iter()
+        .find(|(_, pattern)| {
+            pattern.iter().zip(glyph).all(|(pattern, row)| {
+                pattern.len() == row.len()
+                    && pattern
+                        .chars()
+                        .zip(row.iter())
+                        .all(|(c, &lit)| (c == '#') == lit)
+            })
+        })
+        .map(|(letter, _)| *letter)
+}
+
+fn render(rows: &[&[bool]]) -> String {
+    rows.iter()
+        .map(|row| {
+            row.iter()
+                .map(|&lit| if lit { '#' } else { '.' })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// An answer drawn as ASCII art letters.
+/// Displays as the art itself, use [`Letters::decode`] to recognize the letters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Letters(String);
+
+impl Letters {
+    /// Wrap art drawn with `#` and `.`.
+    pub fn new(art: impl Into<String>) -> Self {
+        Self(art.into())
+    }
+
+    /// Draw rows of pixels, lit pixels are `true`.
+    pub fn from_pixels<R: IntoIterator<Item = bool>>(rows: impl IntoIterator<Item = R>) -> Self {
+        let art = rows
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|lit| if lit { '#' } else { '.' })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        Self(art)
+    }
+
+    /// Recognize the drawn letters.
+    pub fn decode(&self) -> Result<String, OcrError> {
+        recognize(&self.0)
+    }
+}
+
+impl Display for Letters {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{recognize, Letters, OcrError, FONT_10, FONT_6};
+
+    /// Draw glyphs of a font next to each other, separated by blank columns.
+    fn draw<const N: usize>(glyphs: &[[&str; N]], gap: usize) -> String {
+        (0..N)
+            .map(|y| {
+                glyphs
+                    .iter()
+                    .map(|glyph| glyph[y])
+                    .collect::<Vec<_>>()
+                    .join(&".".repeat(gap))
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn recognizes_small_font() {
+        let glyphs: Vec<_> = FONT_6.iter().map(|(_, glyph)| *glyph).collect();
+        let letters: String = FONT_6.iter().map(|(letter, _)| *letter).collect();
+        assert_eq!(recognize(&draw(&glyphs, 1)).unwrap(), letters);
+    }
+
+    #[test]
+    fn recognizes_large_font() {
+        let glyphs: Vec<_> = FONT_10.iter().map(|(_, glyph)| *glyph).collect();
+        let letters: String = FONT_10.iter().map(|(letter, _)| *letter).collect();
+        assert_eq!(recognize(&draw(&glyphs, 2)).unwrap(), letters);
+    }
+
+    #[test]
+    fn recognizes_puzzle_output() {
+        let art = "
+#..#.###...##..####.
+#..#.#..#.#..#.#....
+####.###..#....###..
+#..#.#..#.#....#....
+#..#.#..#.#..#.#....
+#..#.###...##..####.
+";
+        assert_eq!(recognize(art).unwrap(), "HBCE");
+    }
+
+    #[test]
+    fn ignores_unlit_rows_around_art() {
+        let art =
+            "........\n.##..###\n#..#.#..#\n#..#.###\n####.#..#\n#..#.#..#\n#..#.###\n........";
+        assert_eq!(recognize(art).unwrap(), "AB");
+    }
+
+    #[test]
+    fn recognizes_other_pixel_characters() {
+        let art = " ██  ███\n█  █ █  █\n█  █ ███\n████ █  █\n█  █ █  █\n█  █ ███";
+        assert_eq!(recognize(art).unwrap(), "AB");
+    }
+
+    #[test]
+    fn errors_for_unknown_glyph() {
+        let art = "####.#...\n#..#.#...\n#..#.#...\n#..#.#...\n#..#.#...\n####.####";
+        assert_eq!(
+            recognize(art),
+            Err(OcrError::UnknownGlyph {
+                index: 0,
+                glyph: "####\n#..#\n#..#\n#..#\n#..#\n####".into()
+            })
+        );
+    }
+
+    #[test]
+    fn errors_for_unsupported_height() {
+        assert_eq!(recognize("#\n#\n#"), Err(OcrError::UnsupportedHeight(3)));
+    }
+
+    #[test]
+    fn decodes_pixels() {
+        let pixels = FONT_6[0]
+            .1
+            .map(|row| row.chars().map(|c| c == '#').collect::<Vec<_>>());
+        let letters = Letters::from_pixels(pixels);
+        assert_eq!(letters.to_string(), FONT_6[0].1.join("\n"));
+        assert_eq!(letters.decode().unwrap(), "A");
+    }
+}
@@ -0,0 +1,193 @@
+//! A small, seeded pseudo-random number generator for generating puzzle inputs.
+//!
+//! The generator is deterministic for a given seed, so failing inputs can be reproduced.
+//! It is based on [SplitMix64](https://prng.di.unimi.it/splitmix64.c) and is not suitable for cryptographic use.
+use std::ops::{Range, RangeInclusive};
+
+/// A seeded pseudo-random number generator.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Creates a generator from a seed.
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Returns the next random `u64`.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a random value in `0..bound`, without modulo bias.
+    ///
+    /// # Panics
+    /// Panics if `bound` is zero.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        assert!(bound > 0, "bound must be greater than zero");
+        // NOTE: reject values from the incomplete last "bucket" to stay uniform.
+        let zone = u64::MAX - (u64::MAX % bound);
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return value % bound;
+            }
+        }
+    }
+
+    /// Returns a random value in the given range, e.g. `rng.range(1..=6)`.
+    ///
+    /// # Panics
+    /// Panics if the range is empty.
+    pub fn range<T: SampleUniform, R: SampleRange<T>>(&mut self, range: R) -> T {
+        let (low, high) = range.bounds();
+        T::sample(self, low, high)
+    }
+
+    /// Returns `true` with probability `p`.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn chance(&mut self, p: f64) -> bool {
+        // NOTE: use the upper 53 bits, which is the precision of an f64.
+        ((self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64) < p
+    }
+
+    /// Returns a random element of the slice, or `None` if it is empty.
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            None
+        } else {
+            items.get(self.range(0..items.len()))
+        }
+    }
+
+    /// Shuffles the slice in place.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.range(0..=i);
+            items.swap(i, j);
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Integer types that can be sampled uniformly by [`Rng::range`].
+pub trait SampleUniform: Copy {
+    /// Samples a value in the inclusive range `low..=high`.
+    fn sample(rng: &mut Rng, low: Self, high: Self) -> Self;
+}
+
+/// Ranges that can be passed to [`Rng::range`].
+pub trait SampleRange<T> {
+    /// Returns the inclusive bounds of the range.
+    fn bounds(self) -> (T, T);
+}
+
+macro_rules! impl_sample_uniform {
+    ($($t:ty => $unsigned:ty),*) => {
+        $(
+            impl SampleUniform for $t {
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss, clippy::cast_possible_wrap)]
+                fn sample(rng: &mut Rng, low: Self, high: Self) -> Self {
+                    assert!(low <= high, "cannot sample from an empty range");
+                    let span = high.wrapping_sub(low) as $unsigned as u64;
+                    let offset = if span == u64::MAX {
+                        rng.next_u64()
+                    } else {
+                        rng.below(span + 1)
+                    };
+                    low.wrapping_add(offset as $t)
+                }
+            }
+
+            impl SampleRange<$t> for Range<$t> {
+                fn bounds(self) -> ($t, $t) {
+                    assert!(self.start < self.end, "cannot sample from an empty range");
+                    (self.start, self.end - 1)
+                }
+            }
+
+            impl SampleRange<$t> for RangeInclusive<$t> {
+                fn bounds(self) -> ($t, $t) {
+                    self.into_inner()
+                }
+            }
+        )*
+    };
+}
+
+impl_sample_uniform!(
+    u8 => u8, u16 => u16, u32 => u32, u64 => u64, usize => usize,
+    i8 => u8, i16 => u16, i32 => u32, i64 => u64, isize => usize
+);
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Rng;
+
+    #[test]
+    fn is_deterministic() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+        for _ in 0..100 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+    }
+
+    #[test]
+    fn differs_by_seed() {
+        assert_ne!(Rng::new(1).next_u64(), Rng::new(2).next_u64());
+    }
+
+    #[test]
+    fn samples_within_range() {
+        let mut rng = Rng::new(7);
+        for _ in 0..1000 {
+            let x = rng.range(-3..=3_i32);
+            assert!((-3..=3).contains(&x));
+            let y = rng.range(10..20_usize);
+            assert!((10..20).contains(&y));
+        }
+    }
+
+    #[test]
+    fn covers_range() {
+        let mut rng = Rng::new(7);
+        let mut seen = [false; 6];
+        for _ in 0..1000 {
+            seen[rng.range(0..6_usize)] = true;
+        }
+        assert_eq!(seen, [true; 6]);
+    }
+
+    #[test]
+    fn samples_full_range() {
+        let mut rng = Rng::new(7);
+        let _ = rng.range(u64::MIN..=u64::MAX);
+        let _ = rng.range(i64::MIN..=i64::MAX);
+    }
+
+    #[test]
+    fn shuffles_permutation() {
+        let mut rng = Rng::new(3);
+        let mut items: Vec<u32> = (0..50).collect();
+        rng.shuffle(&mut items);
+        assert_ne!(items, (0..50).collect::<Vec<_>>());
+        items.sort_unstable();
+        assert_eq!(items, (0..50).collect::<Vec<_>>());
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_empty_range() {
+        Rng::new(0).range(5..5_u32);
+    }
+}
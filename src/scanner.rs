@@ -0,0 +1,294 @@
+//! A scanner that finds instructions in corrupted text.
+//!
+//! Patterns are either literals such as `do()` or calls such as `mul(12,345)` with a fixed number of
+//! non-negative integer arguments, separated by commas without whitespace.
+//! The input is scanned once from left to right. Matches don't overlap: after a match,
+//! scanning continues behind it.
+//!
+//! ```
+//! use advent_of_code::scanner::{Pattern, Scanner};
+//!
+//! let scanner = Scanner::new([Pattern::call("mul", 2), Pattern::literal("do()")]);
+//! let matches: Vec<_> = scanner.scan("xmul(2,4)do()mul[3,7]").collect();
+//! assert_eq!(matches.len(), 2);
+//! assert_eq!((matches[0].start, matches[0].args()), (1, &[2, 4][..]));
+//! assert_eq!((matches[1].start, matches[1].pattern), (9, 1));
+//! ```
+
+/// The maximum number of arguments of a call.
+pub const MAX_ARGS: usize = 4;
+
+/// A pattern to scan for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Pattern {
+    /// Matches the text exactly.
+    Literal(&'static str),
+    /// Matches `name(a,b,...)` with `args` integer arguments of at most `max_digits` digits.
+    Call {
+        name: &'static str,
+        args: usize,
+        max_digits: usize,
+    },
+}
+
+impl Pattern {
+    pub fn literal(text: &'static str) -> Self {
+        assert!(!text.is_empty(), "literal patterns must not be empty");
+        Self::Literal(text)
+    }
+
+    /// A call with `args` arguments of any length (that fits into a `u64`).
+    pub fn call(name: &'static str, args: usize) -> Self {
+        assert!(!name.is_empty(), "calls must have a name");
+        assert!(
+            args <= MAX_ARGS,
+            "calls can have at most {MAX_ARGS} arguments"
+        );
+        Self::Call {
+            name,
+            args,
+            max_digits: 19,
+        }
+    }
+
+    /// Limit the number of digits of the arguments of a call.
+    pub fn max_digits(self, max_digits: usize) -> Self {
+        match self {
+            Self::Call { name, args, .. } => Self::Call {
+                name,
+                args,
+                max_digits: max_digits.min(19),
+            },
+            literal => literal,
+        }
+    }
+
+    fn first_byte(&self) -> u8 {
+        match self {
+            Self::Literal(text) => text.as_bytes()[0],
+            Self::Call { name, .. } => name.as_bytes()[0],
+        }
+    }
+
+    /// Match the pattern at the start of `bytes`, returning the length of the match and its arguments.
+    fn match_at(&self, bytes: &[u8]) -> Option<(usize, Args)> {
+        match self {
+            Self::Literal(text) => bytes
+                .starts_with(text.as_bytes())
+                .then(|| (text.len(), Args::default())),
+            Self::Call {
+                name,
+                args: count,
+                max_digits,
+            } => {
+                if !bytes.starts_with(name.as_bytes()) || bytes.get(name.len()) != Some(&b'(') {
+                    return None;
+                }
+                let mut len = name.len() + 1;
+                let mut args = Args::default();
+
+                for i in 0..*count {
+                    if i > 0 {
+                        if bytes.get(len) != Some(&b',') {
+                            return None;
+                        }
+                        len += 1;
+                    }
+                    let digits = bytes[len..]
+                        .iter()
+                        .take(max_digits + 1)
+                        .take_while(|b| b.is_ascii_digit())
+                        .count();
+                    if digits == 0 || digits > *max_digits {
+                        return None;
+                    }
+                    let value = bytes[len..len + digits]
+                        .iter()
+                        .fold(0, |value, b| value * 10 + u64::from(b - b'0'));
+                    args.push(value);
+                    len += digits;
+                }
+
+                (bytes.get(len) == Some(&b')')).then(|| (len + 1, args))
+            }
+        }
+    }
+}
+
+/// The arguments of a matched call, stored inline.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Args {
+    values: [u64; MAX_ARGS],
+    len: usize,
+}
+
+impl Args {
+    fn push(&mut self, value: u64) {
+        self.values[self.len] = value;
+        self.len += 1;
+    }
+}
+
+/// A match of a pattern in the scanned text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match<'a> {
+    /// Index of the matched pattern in the scanner.
+    pub pattern: usize,
+    /// Byte offset of the match in the scanned text.
+    pub start: usize,
+    /// The matched text.
+    pub text: &'a str,
+    args: Args,
+}
+
+impl Match<'_> {
+    /// The arguments of a matched call, empty for literals.
+    pub fn args(&self) -> &[u64] {
+        &self.args.values[..self.args.len]
+    }
+}
+
+/// Scans text for a set of patterns. Patterns are tried in order, the first one that matches wins.
+#[derive(Debug, Clone)]
+pub struct Scanner {
+    patterns: Vec<Pattern>,
+    /// Whether any pattern starts with a byte, to skip other bytes quickly.
+    first_bytes: [bool; 256],
+}
+
+impl Scanner {
+    pub fn new(patterns: impl IntoIterator<Item = Pattern>) -> Self {
+        let patterns: Vec<Pattern> = patterns.into_iter().collect();
+        let mut first_bytes = [false; 256];
+        for pattern in &patterns {
+            first_bytes[usize::from(pattern.first_byte())] = true;
+        }
+        Self {
+            patterns,
+            first_bytes,
+        }
+    }
+
+    /// Iterate over the matches in `text`, from left to right.
+    pub fn scan<'s, 'a>(&'s self, text: &'a str) -> Matches<'s, 'a> {
+        Matches {
+            scanner: self,
+            text,
+            position: 0,
+        }
+    }
+}
+
+/// Iterator over the matches of a [`Scanner`].
+#[derive(Debug, Clone)]
+pub struct Matches<'s, 'a> {
+    scanner: &'s Scanner,
+    text: &'a str,
+    position: usize,
+}
+
+impl<'a> Iterator for Matches<'_, 'a> {
+    type Item = Match<'a>;
+
+    fn next(&mut self) -> Option<Match<'a>> {
+        let bytes = self.text.as_bytes();
+
+        while self.position < bytes.len() {
+            let start = self.position;
+            self.position += 1;
+
+            if !self.scanner.first_bytes[usize::from(bytes[start])] {
+                continue;
+            }
+
+            let found = self
+                .scanner
+                .patterns
+                .iter()
+                .enumerate()
+                .find_map(|(i, pattern)| pattern.match_at(&bytes[start..]).map(|m| (i, m)));
+
+            if let Some((pattern, (len, args))) = found {
+                self.position = start + len;
+                return Some(Match {
+                    pattern,
+                    start,
+                    text: &self.text[start..start + len],
+                    args,
+                });
+            }
+        }
+
+        None
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Pattern, Scanner};
+
+    fn scan(scanner: &Scanner, text: &str) -> Vec<(usize, usize, Vec<u64>)> {
+        scanner
+            .scan(text)
+            .map(|m| (m.pattern, m.start, m.args().to_vec()))
+            .collect()
+    }
+
+    #[test]
+    fn matches_calls_and_literals() {
+        let scanner = Scanner::new([
+            Pattern::call("mul", 2).max_digits(3),
+            Pattern::literal("do()"),
+            Pattern::literal("don't()"),
+        ]);
+        let text = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+
+        assert_eq!(
+            scan(&scanner, text),
+            vec![
+                (0, 1, vec![2, 4]),
+                (2, 20, vec![]),
+                (0, 28, vec![5, 5]),
+                (0, 48, vec![11, 8]),
+                (1, 59, vec![]),
+                (0, 64, vec![8, 5]),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_calls() {
+        let scanner = Scanner::new([Pattern::call("mul", 2).max_digits(3)]);
+        for text in [
+            "mul(1, 2)",
+            "mul(1,2",
+            "mul(1,2,3)",
+            "mul(1234,5)",
+            "mul(,5)",
+            "mul (1,2)",
+            "mul(-1,2)",
+        ] {
+            assert_eq!(scan(&scanner, text), vec![], "{text}");
+        }
+    }
+
+    #[test]
+    fn matches_at_edges_of_input() {
+        let scanner = Scanner::new([Pattern::call("mul", 2), Pattern::literal("do()")]);
+        assert_eq!(scan(&scanner, "do()"), vec![(1, 0, vec![])]);
+        assert_eq!(scan(&scanner, "xxdo()"), vec![(1, 2, vec![])]);
+        assert_eq!(scan(&scanner, "mul(mul(1,2)"), vec![(0, 4, vec![1, 2])]);
+        assert_eq!(scan(&scanner, "do(mul(3,4)"), vec![(0, 3, vec![3, 4])]);
+    }
+
+    #[test]
+    fn reports_matched_text() {
+        let scanner = Scanner::new([Pattern::call("add", 3), Pattern::call("noop", 0)]);
+        let matches: Vec<_> = scanner.scan("_add(1,22,333)noop()").collect();
+        assert_eq!(matches[0].text, "add(1,22,333)");
+        assert_eq!(matches[0].args(), &[1, 22, 333]);
+        assert_eq!(matches[1].text, "noop()");
+    }
+}
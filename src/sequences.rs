@@ -0,0 +1,126 @@
+//! Checks on sequences of items.
+
+/// Returns `true` if removing at most `K` items from `items` leaves a sequence
+/// in which every pair of adjacent items satisfies `ok`.
+///
+/// Runs in a single pass in `O(n * K)` time, without allocating.
+///
+/// ```
+/// use advent_of_code::sequences::is_valid_with_removals;
+///
+/// let ascending = |a: &i32, b: &i32| a < b;
+/// assert!(is_valid_with_removals::<_, 1>(&[1, 3, 2, 4], ascending));
+/// assert!(!is_valid_with_removals::<_, 1>(&[1, 3, 2, 1], ascending));
+/// ```
+pub fn is_valid_with_removals<T, const K: usize>(items: &[T], ok: impl Fn(&T, &T) -> bool) -> bool {
+    if K == 0 {
+        return items.windows(2).all(|pair| ok(&pair[0], &pair[1]));
+    }
+    if items.len() <= K {
+        return true;
+    }
+
+    // NOTE: `cost` of a position is the fewest removals before it that leave a valid sequence ending in it.
+    // An item can follow any of the `K + 1` previous items, skipping the ones in between.
+    // Costs of the last `K` positions are kept in a ring buffer, the one before those in `evicted`.
+    let mut costs = [usize::MAX; K];
+    let mut evicted = usize::MAX;
+
+    for (i, item) in items.iter().enumerate() {
+        let mut cost = if i <= K { i } else { usize::MAX };
+
+        for skipped in 0..i.min(K + 1) {
+            let previous = i - skipped - 1;
+            let previous_cost = if skipped == K {
+                evicted
+            } else {
+                costs[previous % K]
+            };
+
+            let candidate = previous_cost.saturating_add(skipped);
+            if candidate < cost.min(K + 1) && ok(&items[previous], item) {
+                cost = candidate;
+            }
+        }
+
+        evicted = costs[i % K];
+        costs[i % K] = cost;
+
+        let removed_after = items.len() - 1 - i;
+        if removed_after <= K && cost <= K - removed_after {
+            return true;
+        }
+    }
+
+    false
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::is_valid_with_removals;
+    use crate::rng::Rng;
+
+    fn ascending(a: &i32, b: &i32) -> bool {
+        a < b && b - a <= 3
+    }
+
+    /// Tries every way of removing at most `k` items.
+    fn brute_force(items: &[i32], k: usize) -> bool {
+        items.windows(2).all(|pair| ascending(&pair[0], &pair[1]))
+            || (k > 0
+                && (0..items.len()).any(|i| {
+                    let mut rest = items.to_vec();
+                    rest.remove(i);
+                    brute_force(&rest, k - 1)
+                }))
+    }
+
+    #[test]
+    fn checks_without_removals() {
+        assert!(is_valid_with_removals::<_, 0>(&[1, 2, 4, 7], ascending));
+        assert!(!is_valid_with_removals::<_, 0>(&[1, 2, 6], ascending));
+        assert!(is_valid_with_removals::<_, 0>(&[], ascending));
+    }
+
+    #[test]
+    fn removes_first_and_last_items() {
+        assert!(is_valid_with_removals::<_, 1>(&[9, 1, 2, 3], ascending));
+        assert!(is_valid_with_removals::<_, 1>(&[1, 2, 3, 0], ascending));
+        assert!(is_valid_with_removals::<_, 2>(&[9, 8, 1, 2], ascending));
+        assert!(!is_valid_with_removals::<_, 1>(&[9, 8, 1, 2], ascending));
+        assert!(is_valid_with_removals::<_, 2>(&[5, 1], ascending));
+    }
+
+    #[test]
+    fn matches_brute_force_on_random_rows() {
+        let mut rng = Rng::new(2);
+
+        for _ in 0..2000 {
+            let len = rng.range(0..9);
+            let row: Vec<i32> = (0..len).map(|_| rng.range(0..10)).collect();
+
+            assert_eq!(
+                is_valid_with_removals::<_, 0>(&row, ascending),
+                brute_force(&row, 0),
+                "{row:?}"
+            );
+            assert_eq!(
+                is_valid_with_removals::<_, 1>(&row, ascending),
+                brute_force(&row, 1),
+                "{row:?}"
+            );
+            assert_eq!(
+                is_valid_with_removals::<_, 2>(&row, ascending),
+                brute_force(&row, 2),
+                "{row:?}"
+            );
+            assert_eq!(
+                is_valid_with_removals::<_, 3>(&row, ascending),
+                brute_force(&row, 3),
+                "{row:?}"
+            );
+        }
+    }
+}
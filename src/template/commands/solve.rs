@@ -1,8 +1,20 @@
 use std::process::{Command, Stdio};
 
+use crate::template::differential::Config;
 use crate::template::Day;
+use crate::visual;
 
-pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
+#[allow(clippy::too_many_arguments)]
+pub fn handle(
+    day: Day,
+    release: bool,
+    dhat: bool,
+    submit_part: Option<u8>,
+    time: bool,
+    trace: Option<String>,
+    differential: Option<Config>,
+    visual: Option<visual::Config>,
+) {
     let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
 
     if dhat {
@@ -16,6 +28,10 @@ pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
         cmd_args.push("--release".to_string());
     }
 
+    if visual.is_some() {
+        cmd_args.extend(["--features".to_string(), "visualize".to_string()]);
+    }
+
     cmd_args.push("--".to_string());
 
     if let Some(submit_part) = submit_part {
@@ -23,6 +39,30 @@ pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
         cmd_args.push(submit_part.to_string());
     }
 
+    if time {
+        cmd_args.push("--time".to_string());
+    }
+
+    if let Some(trace) = trace {
+        cmd_args.push("--trace".to_string());
+        cmd_args.push(trace);
+    }
+
+    if let Some(config) = differential {
+        cmd_args.push("--differential".to_string());
+        cmd_args.extend(config.to_args());
+    }
+
+    if let Some(config) = visual {
+        if config.terminal {
+            cmd_args.push("--visualize".to_string());
+        }
+        if config.render {
+            cmd_args.push("--render".to_string());
+        }
+        cmd_args.extend(["--speed".to_string(), config.speed.to_string()]);
+    }
+
     let mut cmd = Command::new("cargo")
         .args(&cmd_args)
         .stdout(Stdio::inherit())
@@ -0,0 +1,25 @@
+use std::process::{Command, Stdio};
+
+use crate::template::stress::Config;
+use crate::template::Day;
+
+pub fn handle(day: Day, config: Config) {
+    let mut cmd_args = vec![
+        "run".to_string(),
+        "--bin".to_string(),
+        day.to_string(),
+        "--release".to_string(),
+        "--".to_string(),
+        "--stress".to_string(),
+    ];
+    cmd_args.extend(config.to_args());
+
+    let mut cmd = Command::new("cargo")
+        .args(&cmd_args)
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .unwrap();
+
+    cmd.wait().unwrap();
+}
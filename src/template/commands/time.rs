@@ -2,9 +2,16 @@ use std::collections::HashSet;
 
 use crate::template::run_multi::run_multi;
 use crate::template::timings::Timings;
-use crate::template::{all_days, readme_benchmarks, Day};
+use crate::template::{all_days, export, readme_benchmarks, Day, ExportFormat};
 
-pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
+pub fn handle(
+    day: Option<Day>,
+    run_all: bool,
+    store: bool,
+    memory: bool,
+    export: Option<ExportFormat>,
+    out: Option<String>,
+) {
     let stored_timings = Timings::read_from_file();
 
     let days_to_run = day.map_or_else(
@@ -21,20 +28,39 @@ pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
         |day| HashSet::from([day]),
     );
 
-    let timings = run_multi(&days_to_run, true, true).unwrap();
+    let timings = run_multi(&days_to_run, true, true, memory).unwrap();
 
-    if store {
-        let merged_timings = stored_timings.merge(&timings);
-        merged_timings.store_file().unwrap();
+    if let Some(format) = export {
+        let path = out.unwrap_or_else(|| format!("data/benchmarks.{}", format.extension()));
 
         println!();
-        match readme_benchmarks::update(merged_timings) {
+        match export::export(&timings, format, &path) {
             Ok(()) => {
-                println!("Stored updated benchmarks.");
+                println!("Exported benchmarks to \"{path}\".");
             }
-            Err(_) => {
-                eprintln!("Failed to store updated benchmarks.");
+            Err(e) => {
+                eprintln!("Failed to export benchmarks: {e}");
             }
         }
     }
+
+    if store {
+        store_timings(&stored_timings, &timings);
+    }
+}
+
+/// Merge `timings` into the stored timings and update the readme table.
+pub(crate) fn store_timings(stored_timings: &Timings, timings: &Timings) {
+    let merged_timings = stored_timings.merge(timings);
+    merged_timings.store_file().unwrap();
+
+    println!();
+    match readme_benchmarks::update(merged_timings) {
+        Ok(()) => {
+            println!("Stored updated benchmarks.");
+        }
+        Err(_) => {
+            eprintln!("Failed to store updated benchmarks.");
+        }
+    }
 }
@@ -0,0 +1,372 @@
+/// Differential testing of alternative implementations of a part.
+/// Random inputs are generated by the day's generator and every alternative is checked to agree with its part.
+/// Inputs are generated in order of increasing size, so the first disagreement found is also the smallest one.
+/// It is then shrunk further by removing lines for as long as the implementations keep disagreeing.
+use std::any::Any;
+use std::fmt::Display;
+use std::panic::{self, AssertUnwindSafe};
+use std::process;
+
+use crate::template::runner::{Alternative, Part, Solution};
+use crate::template::{ANSI_BOLD, ANSI_RESET};
+
+/// Controls how many inputs are generated.
+#[derive(Debug, Clone, Copy)]
+pub struct Config {
+    /// Number of inputs generated for every size.
+    pub runs: u64,
+    /// Sizes `1..=max_size` are passed to the generator.
+    pub max_size: usize,
+    /// Seed of the first generated input.
+    pub seed: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            runs: 10,
+            max_size: 10,
+            seed: 0,
+        }
+    }
+}
+
+impl Config {
+    /// Read `--runs <n>`, `--max-size <n>` and `--seed <n>`, using the defaults for missing flags.
+    pub fn from_args(args: &mut pico_args::Arguments) -> Result<Self, pico_args::Error> {
+        let defaults = Self::default();
+
+        Ok(Self {
+            runs: args.opt_value_from_str("--runs")?.unwrap_or(defaults.runs),
+            max_size: args
+                .opt_value_from_str("--max-size")?
+                .unwrap_or(defaults.max_size),
+            seed: args.opt_value_from_str("--seed")?.unwrap_or(defaults.seed),
+        })
+    }
+
+    /// The config passed on to a solution's binary by `cargo solve`.
+    pub fn from_env() -> Self {
+        Self::from_args(&mut pico_args::Arguments::from_env()).unwrap_or_else(|e| {
+            eprintln!("Error: {e}");
+            process::exit(1);
+        })
+    }
+
+    /// The flags read by [`from_args`](Self::from_args).
+    pub fn to_args(&self) -> Vec<String> {
+        vec![
+            "--runs".to_string(),
+            self.runs.to_string(),
+            "--max-size".to_string(),
+            self.max_size.to_string(),
+            "--seed".to_string(),
+            self.seed.to_string(),
+        ]
+    }
+}
+
+/// The result of running an implementation on an input.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Answer(Option<String>),
+    Panic(String),
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Answer(Some(answer)) => write!(f, "{answer}"),
+            Outcome::Answer(None) => write!(f, "✖"),
+            Outcome::Panic(message) => write!(f, "panicked: {message}"),
+        }
+    }
+}
+
+/// Two implementations of a part that disagree on an input.
+#[derive(Debug, Clone)]
+pub struct Mismatch {
+    pub part: u8,
+    pub alternative: &'static str,
+    pub seed: u64,
+    pub size: usize,
+    /// The shrunk input the implementations disagree on.
+    pub input: String,
+    pub expected: Outcome,
+    pub actual: Outcome,
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Part {} and `{}` disagree on an input generated with seed {} and size {}:",
+            self.part, self.alternative, self.seed, self.size
+        )?;
+        writeln!(f, "  Part {}: {}", self.part, self.expected)?;
+        writeln!(f, "  {}: {}", self.alternative, self.actual)?;
+        writeln!(f, "Minimal input ({} lines):", self.input.lines().count())?;
+        write!(f, "{}", self.input)
+    }
+}
+
+/// Check all alternatives of `solution` against their parts on generated inputs.
+/// Returns the number of generated inputs, or the first (shrunk) mismatch.
+///
+/// # Panics
+/// Panics if the solution has no generator registered.
+pub fn check(solution: &Solution, config: &Config) -> Result<u64, Box<Mismatch>> {
+    let generator = solution
+        .generator
+        .expect("differential testing requires a registered generator");
+
+    let pairs: Vec<(&Part, &Alternative)> = solution
+        .alternatives
+        .iter()
+        .filter_map(|alt| {
+            let part = solution.parts.iter().find(|p| p.number == alt.part)?;
+            Some((part, alt))
+        })
+        .collect();
+
+    let mut checked = 0;
+
+    for size in 1..=config.max_size {
+        for run in 0..config.runs {
+            let seed = config.seed.wrapping_add(run);
+            let input = generator(seed, size);
+            checked += 1;
+
+            for &(part, alternative) in &pairs {
+                if let Some((expected, actual)) = compare(part, alternative, &input) {
+                    let input = shrink(part, alternative, input, &expected, &actual);
+                    let (expected, actual) = compare(part, alternative, &input)
+                        .expect("shrunk input should still disagree");
+
+                    return Err(Box::new(Mismatch {
+                        part: part.number,
+                        alternative: alternative.name,
+                        seed,
+                        size,
+                        input,
+                        expected,
+                        actual,
+                    }));
+                }
+            }
+        }
+    }
+
+    Ok(checked)
+}
+
+/// Assert that all alternatives of `solution` agree with their parts on `runs` inputs of every size up to 10.
+/// Intended to be called from a day's tests.
+///
+/// # Panics
+/// Panics with the minimal failing input if the implementations disagree.
+pub fn assert_agree(solution: &Solution, runs: u64) {
+    let config = Config {
+        runs,
+        ..Config::default()
+    };
+
+    if let Err(mismatch) = check(solution, &config) {
+        panic!("{mismatch}");
+    }
+}
+
+/// Entry point of `cargo solve <day> --differential`.
+pub fn handle(solution: &Solution, config: &Config) {
+    if solution.generator.is_none() {
+        eprintln!(
+            "Day {} has no generator. Register one with `solution!({}, generator: generate)`.",
+            solution.day,
+            solution.day.into_inner()
+        );
+        process::exit(1);
+    }
+
+    if solution.alternatives.is_empty() {
+        eprintln!("Day {} has no alternative implementations.", solution.day);
+        process::exit(1);
+    }
+
+    println!(
+        "{ANSI_BOLD}Day {}{ANSI_RESET}: checking {} alternative(s) on {} generated inputs...",
+        solution.day,
+        solution.alternatives.len(),
+        config.runs * config.max_size as u64
+    );
+
+    match check(solution, config) {
+        Ok(checked) => println!("All implementations agree on {checked} inputs."),
+        Err(mismatch) => {
+            println!("{mismatch}");
+            process::exit(1);
+        }
+    }
+}
+
+fn run(answer: fn(&str) -> Option<String>, input: &str) -> Outcome {
+    match panic::catch_unwind(AssertUnwindSafe(|| answer(input))) {
+        Ok(answer) => Outcome::Answer(answer),
+        Err(payload) => Outcome::Panic(panic_message(payload.as_ref())),
+    }
+}
+
+/// Extract the message of a caught panic.
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| (*s).to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_default()
+}
+
+fn compare(part: &Part, alternative: &Alternative, input: &str) -> Option<(Outcome, Outcome)> {
+    let expected = run(part.answer, input);
+    let actual = run(alternative.answer, input);
+    (expected != actual).then_some((expected, actual))
+}
+
+fn is_panic(outcome: &Outcome) -> bool {
+    matches!(outcome, Outcome::Panic(_))
+}
+
+/// Greedily remove chunks of lines from `input` while the implementations keep disagreeing in the same way,
+/// i.e. a disagreement on answers does not turn into a disagreement caused by a panic on malformed input.
+fn shrink(
+    part: &Part,
+    alternative: &Alternative,
+    input: String,
+    expected: &Outcome,
+    actual: &Outcome,
+) -> String {
+    let panics = (is_panic(expected), is_panic(actual));
+    let mut lines: Vec<&str> = input.lines().collect();
+    let mut chunk = lines.len() / 2;
+
+    while chunk > 0 {
+        let mut start = 0;
+        let mut removed_any = false;
+
+        while start < lines.len() {
+            let end = (start + chunk).min(lines.len());
+            let candidate: Vec<&str> = [&lines[..start], &lines[end..]].concat();
+            let candidate_input = candidate.join("\n") + "\n";
+
+            let still_fails = compare(part, alternative, &candidate_input)
+                .is_some_and(|(e, a)| (is_panic(&e), is_panic(&a)) == panics);
+
+            if still_fails {
+                lines = candidate;
+                removed_any = true;
+            } else {
+                start += chunk;
+            }
+        }
+
+        if !removed_any {
+            chunk /= 2;
+        }
+    }
+
+    lines.join("\n") + "\n"
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{check, Config, Outcome};
+    use crate::day;
+    use crate::rng::Rng;
+    use crate::template::runner::{Alternative, Part, Solution};
+
+    /// Emits `5 * size` random numbers, one per line.
+    fn numbers(seed: u64, size: usize) -> String {
+        let mut rng = Rng::new(seed);
+        (0..size * 5)
+            .map(|_| format!("{}\n", rng.range(0..100_u32)))
+            .collect()
+    }
+
+    fn sum(input: &str) -> Option<String> {
+        Some(
+            input
+                .lines()
+                .map(|l| l.parse::<u32>().unwrap())
+                .sum::<u32>()
+                .to_string(),
+        )
+    }
+
+    fn sum_in_reverse(input: &str) -> Option<String> {
+        Some(
+            input
+                .lines()
+                .rev()
+                .map(|l| l.parse::<u32>().unwrap())
+                .sum::<u32>()
+                .to_string(),
+        )
+    }
+
+    /// Wrong as soon as the input contains a number above 90.
+    fn sum_capped(input: &str) -> Option<String> {
+        Some(
+            input
+                .lines()
+                .map(|l| l.parse::<u32>().unwrap().min(90))
+                .sum::<u32>()
+                .to_string(),
+        )
+    }
+
+    fn solution(alternative: fn(&str) -> Option<String>) -> Solution {
+        Solution {
+            day: day!(1),
+            parts: Box::leak(Box::new([Part {
+                number: 1,
+                run: |_| {},
+                answer: sum,
+            }])),
+            alternatives: Box::leak(Box::new([Alternative {
+                part: 1,
+                name: "alternative",
+                answer: alternative,
+            }])),
+            generator: Some(numbers),
+        }
+    }
+
+    #[test]
+    fn passes_for_agreeing_implementations() {
+        let checked = check(&solution(sum_in_reverse), &Config::default()).unwrap();
+        assert_eq!(checked, 100);
+    }
+
+    #[test]
+    fn reports_minimal_mismatch() {
+        let mismatch = check(&solution(sum_capped), &Config::default()).unwrap_err();
+        assert_eq!(mismatch.size, 1);
+        assert_eq!(mismatch.input.lines().count(), 1);
+        let number: u32 = mismatch.input.trim().parse().unwrap();
+        assert!(number > 90);
+        assert_eq!(mismatch.expected, Outcome::Answer(Some(number.to_string())));
+        assert_eq!(mismatch.actual, Outcome::Answer(Some("90".into())));
+    }
+
+    #[test]
+    fn forwards_config_as_args() {
+        let config = Config {
+            runs: 3,
+            max_size: 4,
+            seed: 7,
+        };
+        let args = config.to_args().into_iter().map(Into::into).collect();
+        let parsed = Config::from_args(&mut pico_args::Arguments::from_vec(args)).unwrap();
+        assert_eq!((parsed.runs, parsed.max_size, parsed.seed), (3, 4, 7));
+    }
+}
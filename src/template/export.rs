@@ -0,0 +1,332 @@
+/// Module that writes timing information to reports outside of the readme.
+use std::error::Error;
+use std::fmt::Display;
+use std::str::FromStr;
+use std::{fs, io};
+
+use tinyjson::JsonValue;
+
+use crate::template::run_multi::get_path_for_bin;
+use crate::template::timings::{MemoryStats, Statistics, Timing, Timings};
+use crate::template::Day;
+
+/// The file formats that timings can be exported to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    Markdown,
+    Html,
+}
+
+impl ExportFormat {
+    /// The file extension conventionally used for this format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Markdown => "md",
+            ExportFormat::Html => "html",
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = ExportFormatFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "csv" => Ok(ExportFormat::Csv),
+            "json" => Ok(ExportFormat::Json),
+            "md" | "markdown" => Ok(ExportFormat::Markdown),
+            "html" => Ok(ExportFormat::Html),
+            _ => Err(ExportFormatFromStrError),
+        }
+    }
+}
+
+/// An error which can be returned when parsing an [`ExportFormat`].
+#[derive(Debug)]
+pub struct ExportFormatFromStrError;
+
+impl Error for ExportFormatFromStrError {}
+
+impl Display for ExportFormatFromStrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("expecting one of `csv`, `json`, `md` or `html`")
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Write `timings` to `path` in the given format.
+pub fn export(timings: &Timings, format: ExportFormat, path: &str) -> Result<(), io::Error> {
+    fs::write(path, render(timings, format)?)
+}
+
+fn render(timings: &Timings, format: ExportFormat) -> Result<String, io::Error> {
+    match format {
+        ExportFormat::Csv => Ok(to_csv(timings)),
+        ExportFormat::Json => JsonValue::from(timings.clone())
+            .format()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+        ExportFormat::Markdown => Ok(to_markdown(timings)),
+        ExportFormat::Html => Ok(to_html(timings)),
+    }
+}
+
+/// A single benchmarked part, flattened for tabular formats.
+struct Row<'a> {
+    day: Day,
+    part: u8,
+    time: &'a str,
+    stats: Option<&'a Statistics>,
+    memory: Option<&'a MemoryStats>,
+}
+
+fn rows(timings: &Timings) -> impl Iterator<Item = Row<'_>> {
+    timings.data.iter().flat_map(|timing: &Timing| {
+        [
+            (
+                1,
+                &timing.part_1,
+                &timing.part_1_stats,
+                &timing.part_1_memory,
+            ),
+            (
+                2,
+                &timing.part_2,
+                &timing.part_2_stats,
+                &timing.part_2_memory,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(part, time, stats, memory)| {
+            Some(Row {
+                day: timing.day,
+                part,
+                time: time.as_deref()?,
+                stats: stats.as_ref(),
+                memory: memory.as_ref(),
+            })
+        })
+    })
+}
+
+/// Cells shared by the csv, markdown and html formats, in column order.
+const COLUMNS: [&str; 12] = [
+    "day",
+    "part",
+    "time",
+    "samples",
+    "mean_nanos",
+    "median_nanos",
+    "min_nanos",
+    "max_nanos",
+    "std_dev_nanos",
+    "peak_bytes",
+    "total_bytes",
+    "allocations",
+];
+
+fn cells(row: &Row) -> Vec<String> {
+    let mut cells = vec![
+        row.day.into_inner().to_string(),
+        row.part.to_string(),
+        row.time.into(),
+    ];
+
+    match row.stats {
+        Some(stats) => cells.extend([
+            stats.samples.to_string(),
+            format!("{:.1}", stats.mean_nanos),
+            format!("{:.1}", stats.median_nanos),
+            format!("{:.1}", stats.min_nanos),
+            format!("{:.1}", stats.max_nanos),
+            format!("{:.1}", stats.std_dev_nanos),
+        ]),
+        None => cells.extend(std::iter::repeat_n(String::new(), 6)),
+    }
+
+    match row.memory {
+        Some(memory) => cells.extend([
+            memory.peak_bytes.to_string(),
+            memory.total_bytes.to_string(),
+            memory.allocations.to_string(),
+        ]),
+        None => cells.extend(std::iter::repeat_n(String::new(), 3)),
+    }
+
+    cells
+}
+
+fn to_csv(timings: &Timings) -> String {
+    let mut lines = vec![COLUMNS.join(",")];
+    lines.extend(rows(timings).map(|row| cells(&row).join(",")));
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+fn to_markdown(timings: &Timings) -> String {
+    let mut lines = vec![
+        format!("| {} |", COLUMNS.join(" | ")),
+        format!("|{}", " :---: |".repeat(COLUMNS.len())),
+    ];
+
+    for row in rows(timings) {
+        let mut cells = cells(&row);
+        cells[0] = format!(
+            "[Day {}]({})",
+            row.day.into_inner(),
+            get_path_for_bin(row.day)
+        );
+        cells[2] = format!("`{}`", cells[2]);
+        lines.push(format!("| {} |", cells.join(" | ")));
+    }
+
+    lines.push(String::new());
+    lines.push(format!("**Total: {:.2}ms**", timings.total_millis()));
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+fn to_html(timings: &Timings) -> String {
+    let header: String = COLUMNS.iter().map(|c| format!("<th>{c}</th>")).collect();
+
+    let body: Vec<String> = rows(timings)
+        .map(|row| {
+            let cells: String = cells(&row)
+                .iter()
+                .map(|c| format!("<td>{c}</td>"))
+                .collect();
+            format!("      <tr>{cells}</tr>")
+        })
+        .collect();
+
+    [
+        "<!DOCTYPE html>".into(),
+        "<html>".into(),
+        "  <head>".into(),
+        "    <meta charset=\"utf-8\">".into(),
+        "    <title>Benchmarks</title>".into(),
+        "  </head>".into(),
+        "  <body>".into(),
+        "    <table>".into(),
+        format!("      <tr>{header}</tr>"),
+        body.join("\n"),
+        "    </table>".into(),
+        format!(
+            "    <p><strong>Total: {:.2}ms</strong></p>",
+            timings.total_millis()
+        ),
+        "  </body>".into(),
+        "</html>".into(),
+        String::new(),
+    ]
+    .join("\n")
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{render, ExportFormat};
+    use crate::{
+        day,
+        template::timings::{MemoryStats, Statistics, Timing, Timings},
+    };
+
+    fn get_mock_timings() -> Timings {
+        Timings {
+            data: vec![
+                Timing {
+                    day: day!(1),
+                    part_1: Some("10ms".into()),
+                    part_2: Some("20ms".into()),
+                    part_1_stats: Some(Statistics {
+                        samples: 10,
+                        mean_nanos: 1e7,
+                        median_nanos: 1e7,
+                        min_nanos: 9e6,
+                        max_nanos: 11e6,
+                        std_dev_nanos: 5e5,
+                    }),
+                    part_2_stats: None,
+                    part_1_memory: None,
+                    part_2_memory: Some(MemoryStats {
+                        peak_bytes: 2048,
+                        total_bytes: 4096,
+                        allocations: 12,
+                    }),
+                    part_1_spans: vec![],
+                    part_2_spans: vec![],
+                    total_nanos: 3e7,
+                },
+                Timing {
+                    day: day!(4),
+                    part_1: Some("40ms".into()),
+                    part_2: None,
+                    part_1_stats: None,
+                    part_2_stats: None,
+                    part_1_memory: None,
+                    part_2_memory: None,
+                    part_1_spans: vec![],
+                    part_2_spans: vec![],
+                    total_nanos: 4e7,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn parses_formats() {
+        assert_eq!("csv".parse::<ExportFormat>().unwrap(), ExportFormat::Csv);
+        assert_eq!("JSON".parse::<ExportFormat>().unwrap(), ExportFormat::Json);
+        assert_eq!(
+            "md".parse::<ExportFormat>().unwrap(),
+            ExportFormat::Markdown
+        );
+        assert_eq!("html".parse::<ExportFormat>().unwrap(), ExportFormat::Html);
+        assert_eq!("xml".parse::<ExportFormat>().is_err(), true);
+    }
+
+    #[test]
+    fn exports_csv() {
+        let csv = render(&get_mock_timings(), ExportFormat::Csv).unwrap();
+        let expected = [
+            "day,part,time,samples,mean_nanos,median_nanos,min_nanos,max_nanos,std_dev_nanos,peak_bytes,total_bytes,allocations",
+            "1,1,10ms,10,10000000.0,10000000.0,9000000.0,11000000.0,500000.0,,,",
+            "1,2,20ms,,,,,,,2048,4096,12",
+            "4,1,40ms,,,,,,,,,",
+            "",
+        ]
+        .join("\n");
+        assert_eq!(csv, expected);
+    }
+
+    #[test]
+    fn exports_json() {
+        let json = render(&get_mock_timings(), ExportFormat::Json).unwrap();
+        let timings = Timings::try_from(json).unwrap();
+        assert_eq!(timings.data.len(), 2);
+        assert_eq!(timings.data[0].part_1_stats.as_ref().unwrap().samples, 10);
+        assert_eq!(timings.data[1].part_1_stats, None);
+    }
+
+    #[test]
+    fn exports_markdown() {
+        let md = render(&get_mock_timings(), ExportFormat::Markdown).unwrap();
+        assert_eq!(
+            md.contains("| [Day 1](./src/bin/01.rs) | 1 | `10ms` | 10 |"),
+            true
+        );
+        assert_eq!(md.contains("**Total: 70.00ms**"), true);
+    }
+
+    #[test]
+    fn exports_html() {
+        let html = render(&get_mock_timings(), ExportFormat::Html).unwrap();
+        assert_eq!(html.matches("<tr>").count(), 4);
+        assert_eq!(html.contains("<td>40ms</td>"), true);
+    }
+}
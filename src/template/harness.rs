@@ -0,0 +1,196 @@
+/// Benchmark harness used by `cargo bench`.
+/// The harness runs every registered solution part against its puzzle input and reports timings
+/// in the same format as `cargo time`, so results can be stored, exported and compared.
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+use std::{fs, process};
+
+use crate::template::commands::time::store_timings;
+use crate::template::export::{export, ExportFormat};
+use crate::template::runner::{format_duration, print_statistics, sample, Part, Solution};
+use crate::template::timings::{Statistics, Timing, Timings};
+use crate::template::{Day, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+
+static OUTPUT_DIR: &str = "./target/aoc-bench";
+
+struct HarnessArguments {
+    days: HashSet<Day>,
+    save_baseline: Option<String>,
+    baseline: Option<String>,
+    store: bool,
+}
+
+fn parse() -> Result<HarnessArguments, pico_args::Error> {
+    let mut args = pico_args::Arguments::from_env();
+
+    // NOTE: `cargo bench` passes `--bench` to custom harnesses.
+    let _ = args.contains("--bench");
+
+    let save_baseline = args.opt_value_from_str("--save-baseline")?;
+    let baseline = args.opt_value_from_str("--baseline")?;
+    let store = args.contains("--store");
+
+    let mut days = HashSet::new();
+    while let Some(day) = args.opt_free_from_str()? {
+        days.insert(day);
+    }
+
+    let remaining = args.finish();
+    if !remaining.is_empty() {
+        eprintln!("Warning: unknown argument(s): {remaining:?}.");
+    }
+
+    Ok(HarnessArguments {
+        days,
+        save_baseline,
+        baseline,
+        store,
+    })
+}
+
+/// Entry point of the benchmark harness.
+///
+/// `days` is the registry of scaffolded solutions generated by the build script.
+/// Accepts a list of days to filter by, `--save-baseline <name>`, `--baseline <name>` and `--store`.
+pub fn main(days: &[Solution]) {
+    let args = match parse() {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("Error: {err}");
+            process::exit(1);
+        }
+    };
+
+    let baseline = args.baseline.as_ref().map(|name| {
+        Timings::read_from_file_at(&baseline_path(name)).unwrap_or_else(|e| {
+            eprintln!("Failed to read baseline \"{name}\": {e}");
+            process::exit(1);
+        })
+    });
+
+    let mut timings: Vec<Timing> = vec![];
+    let mut need_space = false;
+
+    for solution in days {
+        let day = solution.day;
+
+        if !args.days.is_empty() && !args.days.contains(&day) {
+            continue;
+        }
+
+        if need_space {
+            println!();
+        }
+        need_space = true;
+
+        println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
+        println!("------");
+
+        let Some(input) = read_input(day) else {
+            println!("No input.");
+            continue;
+        };
+
+        let previous = baseline.as_ref().and_then(|b| b.get(day));
+        timings.push(bench_day(day, solution.parts, &input, previous));
+    }
+
+    let timings = Timings { data: timings };
+    let total_millis = timings.total_millis();
+    println!("\n{ANSI_BOLD}Total (Run):{ANSI_RESET} {ANSI_ITALIC}{total_millis:.2}ms{ANSI_RESET}");
+
+    if let Err(e) = fs::create_dir_all(format!("{OUTPUT_DIR}/baselines")) {
+        eprintln!("Failed to create output directory: {e}");
+        process::exit(1);
+    }
+
+    let report_path = format!("{OUTPUT_DIR}/report.html");
+    match export(&timings, ExportFormat::Html, &report_path) {
+        Ok(()) => println!("\nWrote report to \"{report_path}\"."),
+        Err(e) => eprintln!("\nFailed to write report: {e}"),
+    }
+
+    if let Some(name) = args.save_baseline {
+        match timings.store_file_at(&baseline_path(&name)) {
+            Ok(()) => println!("Saved baseline \"{name}\"."),
+            Err(e) => eprintln!("Failed to save baseline \"{name}\": {e}"),
+        }
+    }
+
+    if args.store {
+        store_timings(&Timings::read_from_file(), &timings);
+    }
+}
+
+fn read_input(day: Day) -> Option<String> {
+    fs::read_to_string(format!("data/inputs/{day}.txt"))
+        .ok()
+        .filter(|input| !input.is_empty())
+}
+
+fn baseline_path(name: &str) -> String {
+    format!("{OUTPUT_DIR}/baselines/{name}.json")
+}
+
+fn bench_day(day: Day, parts: &[Part], input: &str, baseline: Option<&Timing>) -> Timing {
+    let mut timing = Timing {
+        day,
+        part_1: None,
+        part_2: None,
+        part_1_stats: None,
+        part_2_stats: None,
+        part_1_memory: None,
+        part_2_memory: None,
+        part_1_spans: vec![],
+        part_2_spans: vec![],
+        total_nanos: 0_f64,
+    };
+
+    for part in parts {
+        let part_str = format!("Part {}", part.number);
+
+        let timer = Instant::now();
+        (part.run)(input);
+        let base_time = timer.elapsed();
+
+        let statistics = Statistics::from_samples(&sample(part.run, input, &base_time));
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let mean = Duration::from_nanos(statistics.mean_nanos as u64);
+
+        let previous = baseline.and_then(|b| match part.number {
+            1 => b.part_1_stats.as_ref(),
+            _ => b.part_2_stats.as_ref(),
+        });
+
+        println!(
+            "{part_str}:{}{}",
+            format_duration(&mean, statistics.samples.into()),
+            previous.map_or_else(String::new, |p| format_change(p, &statistics))
+        );
+        print_statistics(&statistics, &part_str);
+
+        let time = Some(format!("{mean:.1?}"));
+        timing.total_nanos += statistics.mean_nanos;
+
+        if part.number == 1 {
+            timing.part_1 = time;
+            timing.part_1_stats = Some(statistics);
+        } else {
+            timing.part_2 = time;
+            timing.part_2_stats = Some(statistics);
+        }
+    }
+
+    timing
+}
+
+/// Format the relative change in mean run time against a baseline.
+fn format_change(baseline: &Statistics, current: &Statistics) -> String {
+    if baseline.mean_nanos == 0.0 {
+        return String::new();
+    }
+
+    let change = (current.mean_nanos - baseline.mean_nanos) / baseline.mean_nanos * 100.0;
+    format!(" {ANSI_ITALIC}[{change:+.1}% vs. baseline]{ANSI_RESET}")
+}
@@ -2,11 +2,18 @@ use std::{env, fs};
 
 pub mod aoc_cli;
 pub mod commands;
+pub mod differential;
+pub mod harness;
 pub mod runner;
+pub mod spans;
+pub mod stress;
+pub mod trace;
 
 pub use day::*;
+pub use export::ExportFormat;
 
 mod day;
+mod export;
 mod readme_benchmarks;
 mod run_multi;
 mod timings;
@@ -39,30 +46,86 @@ pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
 /// Creates the constant `DAY` and sets up the input and runner for each part.
 ///
 /// The optional, second parameter (1 or 2) allows you to only run a single part of the solution.
+///
+/// Tooling hooks can be registered as trailing options, in this order:
+///  - `generator: generate` registers a `fn(seed: u64, size: usize) -> String` that generates random inputs.
+///  - `alternatives: [2 => part_two_naive]` registers alternative implementations of a part.
+///    These are checked against the part for agreement on generated inputs by `cargo solve <day> --differential`.
+///
+/// Days with a generator can be stress tested on large inputs with `cargo stress <day>`.
 #[macro_export]
 macro_rules! solution {
-    ($day:expr) => {
-        $crate::solution!(@impl $day, [part_one, 1] [part_two, 2]);
+    ($day:expr, 1 $(, $($options:tt)*)?) => {
+        $crate::solution!(@impl $day, [part_one, 1]; $($($options)*)?);
     };
-    ($day:expr, 1) => {
-        $crate::solution!(@impl $day, [part_one, 1]);
+    ($day:expr, 2 $(, $($options:tt)*)?) => {
+        $crate::solution!(@impl $day, [part_two, 2]; $($($options)*)?);
     };
-    ($day:expr, 2) => {
-        $crate::solution!(@impl $day, [part_two, 2]);
+    ($day:expr $(, $($options:tt)*)?) => {
+        $crate::solution!(@impl $day, [part_one, 1] [part_two, 2]; $($($options)*)?);
     };
 
-    (@impl $day:expr, $( [$func:expr, $part:expr] )*) => {
+    (@impl $day:expr, $( [$func:expr, $part:expr] )*;
+        $(generator: $generator:ident $(,)?)?
+        $(alternatives: [$( $alt_part:literal => $alt:ident ),* $(,)?] $(,)?)?
+    ) => {
         /// The current day.
         const DAY: $crate::template::Day = $crate::day!($day);
 
-        #[cfg(feature = "dhat-heap")]
-        #[global_allocator]
-        static ALLOC: dhat::Alloc = dhat::Alloc;
+        /// The parts and tooling hooks of this solution, used by the benchmark harness and differential testing.
+        #[allow(dead_code, unused_variables)]
+        pub const SOLUTION: $crate::template::runner::Solution = $crate::template::runner::Solution {
+            day: DAY,
+            parts: &[
+                $( $crate::template::runner::Part {
+                    number: $part,
+                    run: |input| {
+                        ::std::hint::black_box($func(input));
+                    },
+                    answer: |input| $func(input).map(|answer| answer.to_string()),
+                }, )*
+            ],
+            alternatives: &[
+                $($( $crate::template::runner::Alternative {
+                    part: $alt_part,
+                    name: stringify!($alt),
+                    answer: |input| $alt(input).map(|answer| answer.to_string()),
+                }, )*)?
+            ],
+            generator: {
+                let generator: Option<$crate::template::runner::Generator> = None;
+                $( let generator = Some($generator as $crate::template::runner::Generator); )?
+                generator
+            },
+        };
 
         fn main() {
             use $crate::template::runner::*;
-            let input = $crate::template::read_file("inputs", DAY);
+
+            if ::std::env::args().any(|x| x == "--differential") {
+                $crate::template::differential::handle(
+                    &SOLUTION,
+                    &$crate::template::differential::Config::from_env(),
+                );
+                return;
+            }
+
+            if ::std::env::args().any(|x| x == "--stress") {
+                $crate::template::stress::handle(
+                    &SOLUTION,
+                    &$crate::template::stress::Config::from_env(),
+                );
+                return;
+            }
+
+            $crate::template::trace::start_from_args();
+            $crate::visual::start_from_args(DAY);
+            let input = $crate::template::trace::phase("read input", || {
+                $crate::template::read_file("inputs", DAY)
+            });
             $( run_part($func, &input, DAY, $part); )*
+            $crate::template::trace::finish();
+            $crate::visual::finish();
         }
     };
 }
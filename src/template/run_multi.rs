@@ -7,7 +7,12 @@ use super::{
     timings::{Timing, Timings},
 };
 
-pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -> Option<Timings> {
+pub fn run_multi(
+    days_to_run: &HashSet<Day>,
+    is_release: bool,
+    is_timed: bool,
+    is_memory: bool,
+) -> Option<Timings> {
     let mut timings: Vec<Timing> = Vec::with_capacity(days_to_run.len());
 
     let mut need_space = false;
@@ -29,7 +34,14 @@ pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -
             if output.is_empty() {
                 println!("Not solved.");
             } else {
-                let val = child_commands::parse_exec_time(&output, day);
+                let mut val = child_commands::parse_exec_time(&output, day);
+
+                if is_memory {
+                    let output = child_commands::profile_memory(day).unwrap();
+                    val.part_1_memory = child_commands::parse_memory(&output, "Part 1");
+                    val.part_2_memory = child_commands::parse_memory(&output, "Part 2");
+                }
+
                 timings.push(val);
             }
         });
@@ -68,7 +80,10 @@ pub fn get_path_for_bin(day: Day) -> String {
 /// This module encapsulates interaction with these binaries, both invoking them as well as parsing the timing output.
 pub mod child_commands {
     use super::{get_path_for_bin, Error};
-    use crate::template::Day;
+    use crate::template::{
+        timings::{MemoryStats, SpanTiming, Statistics},
+        Day,
+    };
     use std::{
         io::{BufRead, BufReader},
         path::Path,
@@ -96,11 +111,37 @@ pub mod child_commands {
             args.push("--time");
         }
 
+        run_child(&args, |_| true)
+    }
+
+    /// Run the solution bin for a given day under the heap profiler.
+    /// Heap profiling slows down execution, so this is a separate, untimed run that only forwards the memory summary.
+    pub fn profile_memory(day: Day) -> Result<Vec<String>, Error> {
+        let day_padded = day.to_string();
+        let args = [
+            "run",
+            "--quiet",
+            "--bin",
+            &day_padded,
+            "--profile",
+            "dhat",
+            "--features",
+            "dhat-heap",
+            "--",
+            "--memory",
+        ];
+
+        run_child(&args, |line| line.contains(" memory:"))
+    }
+
+    /// Spawn a cargo child command and grab its stdout lines.
+    /// Stderr is forwarded, stdout lines are forwarded if they match `forward`.
+    fn run_child(args: &[&str], forward: impl Fn(&str) -> bool) -> Result<Vec<String>, Error> {
         // spawn child command with piped stdout/stderr.
         // forward output to stdout/stderr while grabbing stdout lines.
 
         let mut cmd = Command::new("cargo")
-            .args(&args)
+            .args(args)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .spawn()?;
@@ -118,7 +159,9 @@ pub mod child_commands {
 
         for line in stdout.lines() {
             let line = line.unwrap();
-            println!("{line}");
+            if forward(&line) {
+                println!("{line}");
+            }
             output.push(line);
         }
 
@@ -133,6 +176,12 @@ pub mod child_commands {
             day,
             part_1: None,
             part_2: None,
+            part_1_stats: None,
+            part_2_stats: None,
+            part_1_memory: None,
+            part_2_memory: None,
+            part_1_spans: vec![],
+            part_2_spans: vec![],
             total_nanos: 0_f64,
         };
 
@@ -143,19 +192,23 @@ pub mod child_commands {
                     return None;
                 }
 
-                let Some((timing_str, nanos)) = parse_time(l) else {
+                let Some((timing_str, nanos, samples)) = parse_time(l) else {
                     eprintln!("Could not parse timings from line: {l}");
                     return None;
                 };
 
                 let part = l.split(':').next()?;
-                Some((part, timing_str, nanos))
+                Some((part, timing_str, nanos, samples))
             })
-            .for_each(|(part, timing_str, nanos)| {
+            .for_each(|(part, timing_str, nanos, samples)| {
                 if part.contains("Part 1") {
                     timings.part_1 = Some(timing_str.into());
+                    timings.part_1_stats = parse_stats(output, "Part 1", nanos, samples);
+                    timings.part_1_spans = parse_spans(output, "Part 1");
                 } else if part.contains("Part 2") {
                     timings.part_2 = Some(timing_str.into());
+                    timings.part_2_stats = parse_stats(output, "Part 2", nanos, samples);
+                    timings.part_2_spans = parse_spans(output, "Part 2");
                 }
 
                 timings.total_nanos += nanos;
@@ -164,29 +217,127 @@ pub mod child_commands {
         timings
     }
 
+    /// Parse the `Part <n> stats: ...` line printed by the runner when benching.
+    fn parse_stats(
+        output: &[String],
+        part: &str,
+        mean_nanos: f64,
+        samples: u64,
+    ) -> Option<Statistics> {
+        let prefix = format!("{part} stats:");
+        let line = output.iter().find_map(|l| l.strip_prefix(&prefix))?;
+
+        let mut stats = Statistics {
+            samples,
+            mean_nanos,
+            ..Statistics::default()
+        };
+
+        for entry in line.split(',') {
+            let (key, value) = entry.trim().rsplit_once(' ')?;
+            let nanos = parse_duration(value)?;
+            match key {
+                "min" => stats.min_nanos = nanos,
+                "median" => stats.median_nanos = nanos,
+                "max" => stats.max_nanos = nanos,
+                "std dev" => stats.std_dev_nanos = nanos,
+                _ => {}
+            }
+        }
+
+        Some(stats)
+    }
+
+    /// Parse the `Part <n> memory: ...` line printed by the runner when profiling memory.
+    pub fn parse_memory(output: &[String], part: &str) -> Option<MemoryStats> {
+        let prefix = format!("{part} memory:");
+        let line = output.iter().find_map(|l| l.strip_prefix(&prefix))?;
+
+        let mut memory = MemoryStats::default();
+
+        for entry in line.split(',') {
+            let (key, value) = entry.trim().rsplit_once(' ')?;
+            let value = value.trim_end_matches('B').parse().ok()?;
+            match key {
+                "peak" => memory.peak_bytes = value,
+                "total" => memory.total_bytes = value,
+                "allocations" => memory.allocations = value,
+                _ => {}
+            }
+        }
+
+        Some(memory)
+    }
+
+    /// Parse the indented `Part <n> spans:` breakdown printed by the runner when benching.
+    fn parse_spans(output: &[String], part: &str) -> Vec<SpanTiming> {
+        let header = format!("{part} spans:");
+
+        output
+            .iter()
+            .skip_while(|l| **l != header)
+            .skip(1)
+            .take_while(|l| l.starts_with("  "))
+            .filter_map(|l| {
+                let name = l.trim_start();
+                let depth = (l.len() - name.len()) as u64 / 2 - 1;
+                let (name, value) = name.rsplit_once(": ")?;
+
+                if let Some((hits, lookups)) = value.split_once(" hits of ") {
+                    let calls = lookups.split_once(" lookups")?.0;
+                    return Some(SpanTiming {
+                        name: name.into(),
+                        depth,
+                        nanos: 0.0,
+                        calls: calls.parse().ok()?,
+                        hits: Some(hits.parse().ok()?),
+                    });
+                }
+
+                let (duration, calls) = match value.split_once(" (") {
+                    Some((duration, calls)) => {
+                        (duration, calls.strip_suffix(" calls)")?.parse().ok()?)
+                    }
+                    None => (value, 1),
+                };
+
+                Some(SpanTiming {
+                    name: name.into(),
+                    depth,
+                    nanos: parse_duration(duration)?,
+                    calls,
+                    hits: None,
+                })
+            })
+            .collect()
+    }
+
     fn parse_to_float(s: &str, postfix: &str) -> Option<f64> {
         s.split(postfix).next()?.parse().ok()
     }
 
-    fn parse_time(line: &str) -> Option<(&str, f64)> {
-        // for possible time formats, see: https://github.com/rust-lang/rust/blob/1.64.0/library/core/src/time.rs#L1176-L1200
-        let str_timing = line
+    fn parse_time(line: &str) -> Option<(&str, f64, u64)> {
+        let (timing, samples) = line
             .split(" samples)")
             .next()?
             .split('(')
-            .last()?
-            .split('@')
-            .next()?
-            .trim();
+            .next_back()?
+            .split_once('@')?;
+
+        let str_timing = timing.trim();
+        let samples = samples.trim().parse().ok()?;
 
-        let parsed_timing = match str_timing {
+        Some((str_timing, parse_duration(str_timing)?, samples))
+    }
+
+    fn parse_duration(s: &str) -> Option<f64> {
+        // for possible time formats, see: https://github.com/rust-lang/rust/blob/1.64.0/library/core/src/time.rs#L1176-L1200
+        match s {
             s if s.contains("ns") => s.split("ns").next()?.parse::<f64>().ok(),
             s if s.contains("µs") => parse_to_float(s, "µs").map(|x| x * 1000_f64),
             s if s.contains("ms") => parse_to_float(s, "ms").map(|x| x * 1_000_000_f64),
             s => parse_to_float(s, "s").map(|x| x * 1_000_000_000_f64),
-        }?;
-
-        Some((str_timing, parsed_timing))
+        }
     }
 
     /// copied from: https://github.com/rust-lang/rust/blob/1.64.0/library/std/src/macros.rs#L328-L333
@@ -205,7 +356,8 @@ pub mod child_commands {
 
     #[cfg(feature = "test_lib")]
     mod tests {
-        use super::parse_exec_time;
+        use super::{parse_exec_time, parse_memory};
+        use crate::template::timings::SpanTiming;
 
         use crate::day;
 
@@ -239,6 +391,94 @@ pub mod child_commands {
             assert_eq!(res.part_2.unwrap(), "100ms");
         }
 
+        #[test]
+        fn parses_statistics() {
+            let res = parse_exec_time(
+                &[
+                    "Part 1: 0 (74.13ns @ 100 samples)".into(),
+                    "Part 1 stats: min 70.0ns, median 74.0ns, max 1.2µs, std dev 3.5ns".into(),
+                    "Part 2: 10 (74.13ms @ 10 samples)".into(),
+                    "".into(),
+                ],
+                day!(1),
+            );
+            let stats = res.part_1_stats.unwrap();
+            assert_eq!(stats.samples, 100);
+            assert_approx_eq!(stats.mean_nanos, 74.13_f64);
+            assert_approx_eq!(stats.min_nanos, 70_f64);
+            assert_approx_eq!(stats.median_nanos, 74_f64);
+            assert_approx_eq!(stats.max_nanos, 1200_f64);
+            assert_approx_eq!(stats.std_dev_nanos, 3.5_f64);
+            assert_eq!(res.part_2_stats.is_none(), true);
+        }
+
+        #[test]
+        fn parses_memory() {
+            let output = [
+                "Part 1: 0 (1.2ms)".into(),
+                "Part 1 memory: peak 2048B, total 4096B, allocations 12".into(),
+                "Part 2: 10 (2.4ms)".into(),
+            ];
+            let memory = parse_memory(&output, "Part 1").unwrap();
+            assert_eq!(memory.peak_bytes, 2048);
+            assert_eq!(memory.total_bytes, 4096);
+            assert_eq!(memory.allocations, 12);
+            assert_eq!(parse_memory(&output, "Part 2").is_none(), true);
+        }
+
+        #[test]
+        fn parses_spans() {
+            let res = parse_exec_time(
+                &[
+                    "Part 1: 0 (1.2ms @ 100 samples)".into(),
+                    "Part 1 stats: min 1.0ms, median 1.2ms, max 1.5ms, std dev 100.0µs".into(),
+                    "Part 2: 10 (2.4ms @ 100 samples)".into(),
+                    "Part 2 stats: min 2.0ms, median 2.4ms, max 2.9ms, std dev 200.0µs".into(),
+                    "Part 2 spans:".into(),
+                    "  parse: 1.0ms".into(),
+                    "    rules: 600.0µs (3 calls)".into(),
+                    "  sort: 1.4ms".into(),
+                    "    memo: 8 hits of 19 lookups (42.1%)".into(),
+                    "".into(),
+                ],
+                day!(1),
+            );
+            assert!(res.part_1_spans.is_empty());
+            assert_eq!(
+                res.part_2_spans,
+                vec![
+                    SpanTiming {
+                        name: "parse".into(),
+                        depth: 0,
+                        nanos: 1_000_000.0,
+                        calls: 1,
+                        hits: None,
+                    },
+                    SpanTiming {
+                        name: "rules".into(),
+                        depth: 1,
+                        nanos: 600_000.0,
+                        calls: 3,
+                        hits: None,
+                    },
+                    SpanTiming {
+                        name: "sort".into(),
+                        depth: 0,
+                        nanos: 1_400_000.0,
+                        calls: 1,
+                        hits: None,
+                    },
+                    SpanTiming {
+                        name: "memo".into(),
+                        depth: 1,
+                        nanos: 0.0,
+                        calls: 19,
+                        hits: Some(8),
+                    },
+                ]
+            );
+        }
+
         #[test]
         fn parses_missing_parts() {
             let res = parse_exec_time(
@@ -6,58 +6,194 @@ use std::process::Output;
 use std::time::{Duration, Instant};
 use std::{cmp, env, process};
 
+use crate::template::timings::{MemoryStats, SpanTiming, Statistics};
 use crate::template::ANSI_BOLD;
 use crate::template::{aoc_cli, Day, ANSI_ITALIC, ANSI_RESET};
+use crate::template::{spans, trace};
+use crate::{ocr, visual};
+
+/// A day's solution, registered by the [`solution!`](crate::solution) macro.
+///
+/// This allows tooling such as the benchmark harness or differential testing to call into a day's parts
+/// without knowing their result types.
+#[derive(Clone, Copy)]
+pub struct Solution {
+    pub day: Day,
+    pub parts: &'static [Part],
+    /// Alternative implementations of parts, expected to give the same answers.
+    pub alternatives: &'static [Alternative],
+    /// Generates a random puzzle input from a seed and a size.
+    pub generator: Option<Generator>,
+}
+
+/// A type-erased solution part.
+#[derive(Clone, Copy)]
+pub struct Part {
+    pub number: u8,
+    /// Runs the part, discarding the answer.
+    pub run: fn(&str),
+    /// Runs the part and formats the answer.
+    pub answer: fn(&str) -> Option<String>,
+}
+
+/// A type-erased alternative implementation of a part.
+#[derive(Clone, Copy)]
+pub struct Alternative {
+    pub part: u8,
+    pub name: &'static str,
+    /// Runs the implementation and formats the answer.
+    pub answer: fn(&str) -> Option<String>,
+}
+
+/// A function that generates a puzzle input from a seed and a size.
+pub type Generator = fn(u64, usize) -> String;
 
 pub fn run_part<I: Clone, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
     let part_str = format!("Part {part}");
 
-    let (result, duration, samples) =
-        run_timed(func, input, |result| print_result(result, &part_str, ""));
+    let (result, measurement) = run_timed(func, input, &part_str, |result| {
+        visual::cut(&format!("part-{part}"));
+        print_result(result, &part_str, "");
+    });
+
+    print_result(
+        &result,
+        &part_str,
+        &format_duration(&measurement.duration, measurement.samples),
+    );
 
-    print_result(&result, &part_str, &format_duration(&duration, samples));
+    if let Some(statistics) = measurement.statistics {
+        print_statistics(&statistics, &part_str);
+    }
+
+    if let Some(memory) = measurement.memory {
+        print_memory(&memory, &part_str);
+    }
+
+    if !measurement.spans.is_empty() {
+        print_spans(&measurement.spans, &part_str);
+    }
 
     if let Some(result) = result {
         submit_result(result, day, part);
     }
 }
 
+/// The measurements taken while running a solution part.
+struct Measurement {
+    duration: Duration,
+    samples: u128,
+    statistics: Option<Statistics>,
+    memory: Option<MemoryStats>,
+    spans: Vec<SpanTiming>,
+}
+
 /// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
 ///  1. in debug, the function is executed once.
 ///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
+///
+/// When built with the `dhat-heap` feature and passed `--memory`, the heap usage of the first execution is recorded.
+/// When benching, the instrumentation spans of the first execution are recorded.
+/// When tracing, the first execution and the bench are recorded as phases named after the part.
 fn run_timed<I: Clone, T>(
     func: impl Fn(I) -> T,
     input: I,
+    name: &str,
     hook: impl Fn(&T),
-) -> (T, Duration, u128) {
+) -> (T, Measurement) {
+    #[allow(unused_mut)]
+    let mut memory = None;
+
+    let is_timed = env::args().any(|x| x == "--time");
+
     let timer = Instant::now();
-    let result = {
+    let (result, spans) = {
         let input = input.clone();
 
         #[cfg(feature = "dhat-heap")]
-        let _profiler = dhat::Profiler::new_heap();
+        let _profiler = if env::args().any(|x| x == "--memory") {
+            dhat::Profiler::builder().testing().build()
+        } else {
+            dhat::Profiler::new_heap()
+        };
+
+        let (result, spans) = trace::phase(name, || {
+            trace::with_spans(|| {
+                if is_timed {
+                    spans::collect(|| func(input))
+                } else {
+                    (func(input), vec![])
+                }
+            })
+        });
+
+        #[cfg(feature = "dhat-heap")]
+        if env::args().any(|x| x == "--memory") {
+            let stats = dhat::HeapStats::get();
+            memory = Some(MemoryStats {
+                peak_bytes: stats.max_bytes as u64,
+                total_bytes: stats.total_bytes,
+                allocations: stats.total_blocks,
+            });
+        }
 
-        func(input)
+        (result, spans)
     };
     let base_time = timer.elapsed();
 
     hook(&result);
 
-    let run = if std::env::args().any(|x| x == "--time") {
-        bench(func, input, &base_time)
+    let measurement = if is_timed {
+        let (duration, samples, statistics) =
+            trace::phase(&format!("{name} bench"), || bench(func, input, &base_time));
+        Measurement {
+            duration,
+            samples,
+            statistics: Some(statistics),
+            memory,
+            spans,
+        }
     } else {
-        (base_time, 1)
+        Measurement {
+            duration: base_time,
+            samples: 1,
+            statistics: None,
+            memory,
+            spans,
+        }
     };
 
-    (result, run.0, run.1)
+    (result, measurement)
 }
 
-fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (Duration, u128) {
+fn bench<I: Clone, T>(
+    func: impl Fn(I) -> T,
+    input: I,
+    base_time: &Duration,
+) -> (Duration, u128, Statistics) {
     let mut stdout = stdout();
 
     print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
     let _ = stdout.flush();
 
+    let timers = sample(func, input, base_time);
+    let bench_iterations = timers.len() as u128;
+
+    (
+        #[allow(clippy::cast_possible_truncation)]
+        Duration::from_nanos(average_duration(&timers) as u64),
+        bench_iterations,
+        Statistics::from_samples(&timers),
+    )
+}
+
+/// Execute `func` repeatedly and collect the duration of each execution.
+/// The number of samples is chosen so that sampling takes approx. 1 second, clamped to `10..=10000`.
+pub(crate) fn sample<I: Clone, T>(
+    func: impl Fn(I) -> T,
+    input: I,
+    base_time: &Duration,
+) -> Vec<Duration> {
     let bench_iterations =
         (Duration::from_secs(1).as_nanos() / cmp::max(base_time.as_nanos(), 10)).clamp(10, 10000);
 
@@ -69,13 +205,10 @@ fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) ->
         let timer = Instant::now();
         black_box(func(black_box(cloned)));
         timers.push(timer.elapsed());
+        trace::record("iteration", "bench", timer);
     }
 
-    (
-        #[allow(clippy::cast_possible_truncation)]
-        Duration::from_nanos(average_duration(&timers) as u64),
-        bench_iterations,
-    )
+    timers
 }
 
 fn average_duration(numbers: &[Duration]) -> u128 {
@@ -86,7 +219,7 @@ fn average_duration(numbers: &[Duration]) -> u128 {
         / numbers.len() as u128
 }
 
-fn format_duration(duration: &Duration, samples: u128) -> String {
+pub(crate) fn format_duration(duration: &Duration, samples: u128) -> String {
     if samples == 1 {
         format!(" ({duration:.1?})")
     } else {
@@ -94,6 +227,56 @@ fn format_duration(duration: &Duration, samples: u128) -> String {
     }
 }
 
+pub(crate) fn print_statistics(statistics: &Statistics, part: &str) {
+    let format = |nanos: f64| {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let duration = Duration::from_nanos(nanos as u64);
+        format!("{duration:.1?}")
+    };
+
+    println!(
+        "{part} stats: min {}, median {}, max {}, std dev {}",
+        format(statistics.min_nanos),
+        format(statistics.median_nanos),
+        format(statistics.max_nanos),
+        format(statistics.std_dev_nanos),
+    );
+}
+
+fn print_memory(memory: &MemoryStats, part: &str) {
+    println!(
+        "{part} memory: peak {}B, total {}B, allocations {}",
+        memory.peak_bytes, memory.total_bytes, memory.allocations
+    );
+}
+
+fn print_spans(spans: &[SpanTiming], part: &str) {
+    println!("{part} spans:");
+
+    for span in spans {
+        let indent = "  ".repeat(span.depth as usize + 1);
+
+        if let Some(hits) = span.hits {
+            #[allow(clippy::cast_precision_loss)]
+            let rate = 100.0 * hits as f64 / span.calls.max(1) as f64;
+            println!(
+                "{indent}{}: {hits} hits of {} lookups ({rate:.1}%)",
+                span.name, span.calls
+            );
+            continue;
+        }
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let duration = Duration::from_nanos(span.nanos as u64);
+        let calls = if span.calls > 1 {
+            format!(" ({} calls)", span.calls)
+        } else {
+            String::new()
+        };
+        println!("{indent}{}: {duration:.1?}{calls}", span.name);
+    }
+}
+
 fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str) {
     let is_intermediate_result = duration_str.is_empty();
 
@@ -107,6 +290,9 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
                     print!("\r");
                     println!("{str}");
                     println!("{result}");
+                    if let Ok(letters) = ocr::recognize(&result.to_string()) {
+                        println!("{part} letters: {ANSI_BOLD}{letters}{ANSI_RESET}");
+                    }
                 }
             } else {
                 let str = format!("{part}: {ANSI_BOLD}{result}{ANSI_RESET}{duration_str}");
@@ -159,11 +345,25 @@ fn submit_result<T: Display>(
         return None;
     }
 
+    let answer = submission(&result.to_string()).unwrap_or_else(|e| {
+        eprintln!("Cannot submit multi-line result: {e}");
+        process::exit(1);
+    });
+
     if aoc_cli::check().is_err() {
         eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
         process::exit(1);
     }
 
     println!("Submitting result via aoc-cli...");
-    Some(aoc_cli::submit(day, part, &result.to_string()))
+    Some(aoc_cli::submit(day, part, &answer))
+}
+
+/// The answer to submit for a result. Multi-line results are letters drawn as ASCII art, submit the recognized letters.
+fn submission(result: &str) -> Result<String, ocr::OcrError> {
+    if result.contains('\n') {
+        ocr::recognize(result)
+    } else {
+        Ok(result.to_string())
+    }
 }
@@ -0,0 +1,240 @@
+/// Instrumentation spans for timing the phases of a solution.
+///
+/// Spans are recorded into a thread-local collector while the runner benches a part with `cargo time`
+/// and printed as an indented breakdown below the part's timing.
+/// Spans are also recorded in traces written with `--trace`, see [`trace`](crate::template::trace).
+/// Otherwise, creating a span does not read the clock and is close to free.
+///
+/// Spans are created with the [`span!`](crate::span) macro and are closed when the enclosing scope ends.
+/// Spans of the same name under the same parent are aggregated, so a span inside a loop reports its total time and number of calls.
+use std::cell::RefCell;
+use std::time::{Duration, Instant};
+
+use crate::template::timings::SpanTiming;
+use crate::template::trace;
+
+thread_local! {
+    static COLLECTOR: RefCell<Option<Collector>> = const { RefCell::new(None) };
+}
+
+#[derive(Default)]
+struct Collector {
+    nodes: Vec<Node>,
+    /// Indices of the currently open spans.
+    stack: Vec<usize>,
+}
+
+struct Node {
+    name: &'static str,
+    parent: Option<usize>,
+    total: Duration,
+    calls: u64,
+    /// Cache hits among the calls, for nodes recording cache lookups.
+    hits: Option<u64>,
+}
+
+impl Collector {
+    /// The index of the node named `name` below the innermost open span, created if needed.
+    fn node(&mut self, name: &'static str) -> usize {
+        let parent = self.stack.last().copied();
+
+        self.nodes
+            .iter()
+            .position(|n| n.parent == parent && n.name == name)
+            .unwrap_or_else(|| {
+                self.nodes.push(Node {
+                    name,
+                    parent,
+                    total: Duration::ZERO,
+                    calls: 0,
+                    hits: None,
+                });
+                self.nodes.len() - 1
+            })
+    }
+}
+
+/// An open span, closed when dropped.
+#[must_use = "the span is closed as soon as it is dropped"]
+pub struct Span {
+    name: &'static str,
+    /// Index of the span in the collector, if spans are being collected.
+    index: Option<usize>,
+    /// Start of the span, if it is being collected or traced.
+    start: Option<Instant>,
+}
+
+/// Open a span named `name` below the innermost open span.
+/// Returns a no-op span if spans are not being collected.
+pub fn enter(name: &'static str) -> Span {
+    let index = COLLECTOR.with_borrow_mut(|collector| {
+        let collector = collector.as_mut()?;
+        let index = collector.node(name);
+        collector.stack.push(index);
+        Some(index)
+    });
+
+    let is_timed = index.is_some() || trace::is_recording_spans();
+
+    Span {
+        name,
+        index,
+        start: is_timed.then(Instant::now),
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        let Some(start) = self.start else {
+            return;
+        };
+        let elapsed = start.elapsed();
+
+        if let Some(index) = self.index {
+            COLLECTOR.with_borrow_mut(|collector| {
+                if let Some(collector) = collector.as_mut() {
+                    let node = &mut collector.nodes[index];
+                    node.total += elapsed;
+                    node.calls += 1;
+                    collector.stack.pop();
+                }
+            });
+        }
+
+        trace::record_span(self.name, start);
+    }
+}
+
+/// Count a lookup in a cache named `name` below the innermost open span, e.g. by a [`Memo`](crate::memo::Memo).
+/// Lookups are reported with their hit rate instead of a duration.
+pub fn record_lookup(name: &'static str, hit: bool) {
+    COLLECTOR.with_borrow_mut(|collector| {
+        if let Some(collector) = collector.as_mut() {
+            let index = collector.node(name);
+            let node = &mut collector.nodes[index];
+            node.calls += 1;
+            *node.hits.get_or_insert(0) += u64::from(hit);
+        }
+    });
+}
+
+/// Returns `true` if spans are currently being collected on this thread.
+pub fn is_collecting() -> bool {
+    COLLECTOR.with_borrow(Option::is_some)
+}
+
+/// Run `f` while collecting the spans it opens on the current thread.
+/// Returns the spans in depth-first order.
+pub(crate) fn collect<T>(f: impl FnOnce() -> T) -> (T, Vec<SpanTiming>) {
+    let previous = COLLECTOR.replace(Some(Collector::default()));
+    let result = f();
+    let collector = COLLECTOR.replace(previous).unwrap_or_default();
+
+    let mut spans = vec![];
+    flatten(&collector.nodes, None, 0, &mut spans);
+    (result, spans)
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn flatten(nodes: &[Node], parent: Option<usize>, depth: u64, spans: &mut Vec<SpanTiming>) {
+    for (index, node) in nodes.iter().enumerate() {
+        if node.parent == parent {
+            spans.push(SpanTiming {
+                name: node.name.into(),
+                depth,
+                nanos: node.total.as_nanos() as f64,
+                calls: node.calls,
+                hits: node.hits,
+            });
+            flatten(nodes, Some(index), depth + 1, spans);
+        }
+    }
+}
+
+/// Open an instrumentation span that is timed when running with `--time`.
+///
+/// `span!("name")` opens a span that lasts until the end of the enclosing scope.
+/// `span!("name", expr)` evaluates `expr` inside a span and returns its value.
+///
+/// ```ignore
+/// pub fn part_two(input: &str) -> Option<u32> {
+///     let (rules, updates) = advent_of_code::span!("parse", parse_input(input));
+///     advent_of_code::span!("sort");
+///     // ...
+/// }
+/// ```
+#[macro_export]
+macro_rules! span {
+    ($name:expr) => {
+        let _span = $crate::template::spans::enter($name);
+    };
+    ($name:expr, $body:expr) => {{
+        let _span = $crate::template::spans::enter($name);
+        $body
+    }};
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{collect, enter, is_collecting};
+
+    fn names(spans: &[crate::template::timings::SpanTiming]) -> Vec<(&str, u64, u64)> {
+        spans
+            .iter()
+            .map(|s| (s.name.as_str(), s.depth, s.calls))
+            .collect()
+    }
+
+    #[test]
+    fn is_noop_outside_collection() {
+        assert!(!is_collecting());
+        let span = enter("ignored");
+        assert!(span.index.is_none());
+        assert!(span.start.is_none());
+    }
+
+    #[test]
+    fn records_nested_spans() {
+        let (result, spans) = collect(|| {
+            assert!(is_collecting());
+            crate::span!("parse");
+            let value = crate::span!("solve", { crate::span!("inner", 21) * 2 });
+            value
+        });
+
+        assert_eq!(result, 42);
+        assert_eq!(
+            names(&spans),
+            vec![("parse", 0, 1), ("solve", 1, 1), ("inner", 2, 1)]
+        );
+        assert!(!is_collecting());
+    }
+
+    #[test]
+    fn aggregates_repeated_spans() {
+        let (_, spans) = collect(|| {
+            for _ in 0..3 {
+                crate::span!("step");
+            }
+            crate::span!("done", ());
+        });
+
+        assert_eq!(names(&spans), vec![("step", 0, 3), ("done", 0, 1)]);
+    }
+
+    #[test]
+    fn counts_lookups() {
+        let (_, spans) = collect(|| {
+            crate::span!("solve");
+            for hit in [false, true, true] {
+                super::record_lookup("cache", hit);
+            }
+        });
+
+        assert_eq!(names(&spans), vec![("solve", 0, 1), ("cache", 1, 3)]);
+        assert_eq!(spans[1].hits, Some(2));
+        assert_eq!(spans[0].hits, None);
+    }
+}
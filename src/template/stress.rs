@@ -0,0 +1,401 @@
+/// Stress testing of a solution on generated inputs.
+/// Every part is run on inputs of increasing size generated by the day's generator.
+/// Panics and runs exceeding the timeout are reported together with the seed and size that caused them,
+/// and the mean run time per size shows how the parts scale with the input.
+use std::str::FromStr;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+use std::{panic, process, thread};
+
+use crate::template::differential::panic_message;
+use crate::template::runner::{Generator, Part, Solution};
+use crate::template::{ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+
+/// Controls which inputs are generated.
+#[derive(Debug, Clone, Copy)]
+pub struct Config {
+    /// Number of inputs generated for every size.
+    pub runs: u64,
+    /// The largest input is `scale` times the size of the smallest one.
+    pub scale: Scale,
+    /// Seed of the first generated input.
+    pub seed: u64,
+    /// Maximum time a single run may take.
+    pub timeout: Duration,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            runs: 10,
+            scale: Scale(10),
+            seed: 0,
+            timeout: Duration::from_secs(1),
+        }
+    }
+}
+
+impl Config {
+    /// Read `--size <scale>`, `--runs <n>`, `--seed <n>` and `--timeout <ms>`, using the defaults for missing flags.
+    pub fn from_args(args: &mut pico_args::Arguments) -> Result<Self, pico_args::Error> {
+        let defaults = Self::default();
+
+        Ok(Self {
+            runs: args.opt_value_from_str("--runs")?.unwrap_or(defaults.runs),
+            scale: args.opt_value_from_str("--size")?.unwrap_or(defaults.scale),
+            seed: args.opt_value_from_str("--seed")?.unwrap_or(defaults.seed),
+            timeout: args
+                .opt_value_from_str("--timeout")?
+                .map_or(defaults.timeout, Duration::from_millis),
+        })
+    }
+
+    /// The config passed on to a solution's binary by `cargo stress`.
+    pub fn from_env() -> Self {
+        Self::from_args(&mut pico_args::Arguments::from_env()).unwrap_or_else(|e| {
+            eprintln!("Error: {e}");
+            process::exit(1);
+        })
+    }
+
+    /// The flags read by [`from_args`](Self::from_args).
+    pub fn to_args(&self) -> Vec<String> {
+        vec![
+            "--size".to_string(),
+            self.scale.0.to_string(),
+            "--runs".to_string(),
+            self.runs.to_string(),
+            "--seed".to_string(),
+            self.seed.to_string(),
+            "--timeout".to_string(),
+            self.timeout.as_millis().to_string(),
+        ]
+    }
+}
+
+/// Growth factor of the generated inputs, written as `10x` or `10`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Scale(pub usize);
+
+impl Scale {
+    /// The sizes passed to the generator: powers of two up to the scale, followed by the scale itself.
+    pub fn sizes(self) -> Vec<usize> {
+        let mut sizes: Vec<usize> = std::iter::successors(Some(1_usize), |s| s.checked_mul(2))
+            .take_while(|&s| s < self.0)
+            .collect();
+        sizes.push(self.0);
+        sizes
+    }
+}
+
+#[derive(Debug)]
+pub struct ScaleFromStrError;
+
+impl std::error::Error for ScaleFromStrError {}
+
+impl std::fmt::Display for ScaleFromStrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("expecting a scale greater than zero, e.g. `10x`")
+    }
+}
+
+impl FromStr for Scale {
+    type Err = ScaleFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits = s.strip_suffix('x').unwrap_or(s);
+        match digits.parse() {
+            Ok(scale) if scale > 0 => Ok(Self(scale)),
+            _ => Err(ScaleFromStrError),
+        }
+    }
+}
+
+/// The result of running a part on a generated input.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Completed(Duration),
+    Panic(String),
+    Timeout,
+}
+
+/// A run that panicked or timed out.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Failure {
+    pub seed: u64,
+    pub size: usize,
+    pub outcome: Outcome,
+}
+
+/// The run times of a part on inputs of one size.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SizeReport {
+    pub size: usize,
+    pub completed: u64,
+    pub mean: Duration,
+    pub max: Duration,
+}
+
+/// The results of stress testing a part.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PartReport {
+    pub sizes: Vec<SizeReport>,
+    pub failures: Vec<Failure>,
+}
+
+/// Run `part` on generated inputs of every size.
+/// Larger sizes are skipped once a run times out, as they would most likely time out as well.
+pub fn stress_part(part: &Part, generator: Generator, config: &Config) -> PartReport {
+    let mut report = PartReport::default();
+
+    for size in config.scale.sizes() {
+        let mut durations = vec![];
+        let mut timed_out = false;
+
+        for run in 0..config.runs {
+            let seed = config.seed.wrapping_add(run);
+            let input = generator(seed, size);
+
+            match run_with_timeout(part.run, input, config.timeout) {
+                Outcome::Completed(duration) => durations.push(duration),
+                outcome => {
+                    timed_out |= outcome == Outcome::Timeout;
+                    report.failures.push(Failure {
+                        seed,
+                        size,
+                        outcome,
+                    });
+                }
+            }
+        }
+
+        if !durations.is_empty() {
+            #[allow(clippy::cast_possible_truncation)]
+            let mean = durations.iter().sum::<Duration>() / durations.len() as u32;
+            report.sizes.push(SizeReport {
+                size,
+                completed: durations.len() as u64,
+                mean,
+                max: durations.iter().max().copied().unwrap_or_default(),
+            });
+        }
+
+        if timed_out {
+            break;
+        }
+    }
+
+    report
+}
+
+/// Entry point of `cargo stress <day>`.
+pub fn handle(solution: &Solution, config: &Config) {
+    let Some(generator) = solution.generator else {
+        eprintln!(
+            "Day {} has no generator. Register one with `solution!({}, generator: generate)`.",
+            solution.day,
+            solution.day.into_inner()
+        );
+        process::exit(1);
+    };
+
+    println!(
+        "{ANSI_BOLD}Day {}{ANSI_RESET}: stress testing with {} inputs per size, up to size {}...",
+        solution.day, config.runs, config.scale.0
+    );
+
+    // NOTE: panics are reported below, silence the default hook printing each of them.
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+
+    let mut failed = false;
+
+    for part in solution.parts {
+        let report = stress_part(part, generator, config);
+        println!("\nPart {}", part.number);
+        print_report(&report, config.timeout);
+        failed |= !report.failures.is_empty();
+    }
+
+    panic::set_hook(hook);
+
+    // NOTE: threads of timed out runs are still running, exit instead of waiting for them.
+    process::exit(i32::from(failed));
+}
+
+/// Run `func` on a separate thread, so that runs exceeding `timeout` can be abandoned.
+fn run_with_timeout(func: fn(&str), input: String, timeout: Duration) -> Outcome {
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        let timer = Instant::now();
+        let result = panic::catch_unwind(|| func(&input));
+        let elapsed = timer.elapsed();
+        let _ = sender.send(result.map(|()| elapsed));
+    });
+
+    match receiver.recv_timeout(timeout) {
+        Ok(Ok(duration)) => Outcome::Completed(duration),
+        Ok(Err(payload)) => Outcome::Panic(panic_message(payload.as_ref())),
+        Err(_) => Outcome::Timeout,
+    }
+}
+
+fn print_report(report: &PartReport, timeout: Duration) {
+    let mut previous: Option<&SizeReport> = None;
+
+    for size in &report.sizes {
+        println!(
+            "  size {:>4}: mean {:.1?}, max {:.1?} ({} runs){}",
+            size.size,
+            size.mean,
+            size.max,
+            size.completed,
+            previous.map_or_else(String::new, |p| format_scaling(p, size))
+        );
+        previous = Some(size);
+    }
+
+    for failure in &report.failures {
+        let reason = match &failure.outcome {
+            Outcome::Panic(message) => format!("panicked: {message}"),
+            Outcome::Timeout => format!("timed out after {timeout:.1?}"),
+            Outcome::Completed(_) => continue,
+        };
+        println!(
+            "  {ANSI_BOLD}seed {}, size {}{ANSI_RESET}: {reason}",
+            failure.seed, failure.size
+        );
+    }
+
+    if report.failures.is_empty() {
+        println!("  No panics or timeouts.");
+    }
+}
+
+/// Format the growth of the mean run time between two sizes as an exponent, i.e. `n^2.0` for quadratic growth.
+#[allow(clippy::cast_precision_loss)]
+fn format_scaling(previous: &SizeReport, current: &SizeReport) -> String {
+    let time_ratio = current.mean.as_secs_f64() / previous.mean.as_secs_f64();
+    let size_ratio = current.size as f64 / previous.size as f64;
+
+    if !time_ratio.is_finite() || time_ratio <= 0.0 {
+        return String::new();
+    }
+
+    let exponent = time_ratio.ln() / size_ratio.ln();
+    format!(" {ANSI_ITALIC}[~n^{exponent:.1}]{ANSI_RESET}")
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::time::Duration;
+
+    use super::{stress_part, Config, Outcome, Scale};
+    use crate::template::runner::Part;
+
+    fn lines(seed: u64, size: usize) -> String {
+        format!("{seed}\n").repeat(size)
+    }
+
+    fn part(run: fn(&str)) -> Part {
+        Part {
+            number: 1,
+            run,
+            answer: |_| None,
+        }
+    }
+
+    #[test]
+    fn parses_scale() {
+        assert_eq!("10x".parse::<Scale>().unwrap(), Scale(10));
+        assert_eq!("3".parse::<Scale>().unwrap(), Scale(3));
+        assert!("0x".parse::<Scale>().is_err());
+        assert!("x".parse::<Scale>().is_err());
+    }
+
+    #[test]
+    fn forwards_config_as_args() {
+        let config = Config {
+            runs: 3,
+            scale: Scale(4),
+            seed: 7,
+            timeout: Duration::from_millis(250),
+        };
+        let args = config.to_args().into_iter().map(Into::into).collect();
+        let parsed = Config::from_args(&mut pico_args::Arguments::from_vec(args)).unwrap();
+        assert_eq!(
+            (parsed.runs, parsed.scale, parsed.seed, parsed.timeout),
+            (3, Scale(4), 7, Duration::from_millis(250))
+        );
+
+        let defaults = Config::from_args(&mut pico_args::Arguments::from_vec(vec![])).unwrap();
+        assert_eq!(defaults.scale, Config::default().scale);
+    }
+
+    #[test]
+    fn doubles_sizes_up_to_scale() {
+        assert_eq!(Scale(1).sizes(), vec![1]);
+        assert_eq!(Scale(8).sizes(), vec![1, 2, 4, 8]);
+        assert_eq!(Scale(10).sizes(), vec![1, 2, 4, 8, 10]);
+    }
+
+    #[test]
+    fn reports_run_times_per_size() {
+        let config = Config {
+            runs: 3,
+            scale: Scale(4),
+            ..Config::default()
+        };
+        let report = stress_part(&part(|input| assert!(!input.is_empty())), lines, &config);
+        assert!(report.failures.is_empty());
+        let sizes: Vec<_> = report.sizes.iter().map(|s| (s.size, s.completed)).collect();
+        assert_eq!(sizes, vec![(1, 3), (2, 3), (4, 3)]);
+    }
+
+    #[test]
+    fn reports_panics() {
+        let config = Config {
+            runs: 2,
+            scale: Scale(2),
+            ..Config::default()
+        };
+        let report = stress_part(
+            &part(|input| assert!(input.lines().count() < 2, "too long")),
+            lines,
+            &config,
+        );
+        assert_eq!(report.sizes.len(), 1);
+        assert_eq!(report.failures.len(), 2);
+        assert!(report.failures.iter().all(|f| f.size == 2));
+        assert_eq!(
+            report.failures[0].outcome,
+            Outcome::Panic("too long".into())
+        );
+    }
+
+    #[test]
+    fn stops_after_timeout() {
+        let config = Config {
+            runs: 1,
+            scale: Scale(4),
+            timeout: Duration::from_millis(20),
+            ..Config::default()
+        };
+        let report = stress_part(
+            &part(|input| {
+                if input.lines().count() > 1 {
+                    std::thread::sleep(Duration::from_millis(200));
+                }
+            }),
+            lines,
+            &config,
+        );
+        assert_eq!(report.sizes.len(), 1);
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].size, 2);
+        assert_eq!(report.failures[0].outcome, Outcome::Timeout);
+    }
+}
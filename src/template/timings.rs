@@ -1,4 +1,4 @@
-use std::{collections::HashMap, fs, io::Error, str::FromStr};
+use std::{collections::HashMap, fs, io::Error, str::FromStr, time::Duration};
 use tinyjson::JsonValue;
 
 use crate::template::Day;
@@ -11,9 +11,78 @@ pub struct Timing {
     pub day: Day,
     pub part_1: Option<String>,
     pub part_2: Option<String>,
+    pub part_1_stats: Option<Statistics>,
+    pub part_2_stats: Option<Statistics>,
+    pub part_1_memory: Option<MemoryStats>,
+    pub part_2_memory: Option<MemoryStats>,
+    pub part_1_spans: Vec<SpanTiming>,
+    pub part_2_spans: Vec<SpanTiming>,
     pub total_nanos: f64,
 }
 
+/// Summary statistics over the samples of a benchmarked part.
+/// All durations are stored in nanoseconds.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Statistics {
+    pub samples: u64,
+    pub mean_nanos: f64,
+    pub median_nanos: f64,
+    pub min_nanos: f64,
+    pub max_nanos: f64,
+    pub std_dev_nanos: f64,
+}
+
+/// Heap usage of a single execution of a part, as recorded by the heap profiler.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MemoryStats {
+    pub peak_bytes: u64,
+    pub total_bytes: u64,
+    pub allocations: u64,
+}
+
+/// Aggregated time spent in an instrumentation span during a single execution of a part.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SpanTiming {
+    pub name: String,
+    /// Nesting depth, `0` for top-level spans.
+    pub depth: u64,
+    pub nanos: f64,
+    pub calls: u64,
+    /// Cache hits among the calls, if the span records the lookups of a cache.
+    pub hits: Option<u64>,
+}
+
+impl Statistics {
+    /// Compute statistics from a set of benchmark samples.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+
+        let mut nanos: Vec<f64> = samples.iter().map(|d| d.as_nanos() as f64).collect();
+        nanos.sort_unstable_by(f64::total_cmp);
+
+        let len = nanos.len();
+        let mean = nanos.iter().sum::<f64>() / len as f64;
+        let median = if len.is_multiple_of(2) {
+            (nanos[len / 2 - 1] + nanos[len / 2]) / 2.0
+        } else {
+            nanos[len / 2]
+        };
+        let variance = nanos.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / len as f64;
+
+        Self {
+            samples: len as u64,
+            mean_nanos: mean,
+            median_nanos: median,
+            min_nanos: nanos[0],
+            max_nanos: nanos[len - 1],
+            std_dev_nanos: variance.sqrt(),
+        }
+    }
+}
+
 /// Represents benchmark times for a set of days.
 /// Can be serialized from / to JSON.
 #[derive(Clone, Debug, Default)]
@@ -24,17 +93,31 @@ pub struct Timings {
 impl Timings {
     /// Dehydrate timings to a JSON file.
     pub fn store_file(&self) -> Result<(), Error> {
+        self.store_file_at(TIMINGS_FILE_PATH)
+    }
+
+    /// Dehydrate timings to a JSON file at the given path.
+    pub fn store_file_at(&self, path: &str) -> Result<(), Error> {
         let json = JsonValue::from(self.clone());
-        let mut file = fs::File::create(TIMINGS_FILE_PATH)?;
+        let mut file = fs::File::create(path)?;
         json.format_to(&mut file)
     }
 
     /// Rehydrate timings from a JSON file. If not present, returns empty timings.
     pub fn read_from_file() -> Self {
-        fs::read_to_string(TIMINGS_FILE_PATH)
+        Self::read_from_file_at(TIMINGS_FILE_PATH).unwrap_or_default()
+    }
+
+    /// Rehydrate timings from a JSON file at the given path.
+    pub fn read_from_file_at(path: &str) -> Result<Self, String> {
+        fs::read_to_string(path)
             .map_err(|x| x.to_string())
             .and_then(Timings::try_from)
-            .unwrap_or_default()
+    }
+
+    /// Look up the timing of a single day.
+    pub fn get(&self, day: Day) -> Option<&Timing> {
+        self.data.iter().find(|t| t.day == day)
     }
 
     /// Merge two sets of timings, overwriting `self` with `other` if present.
@@ -51,7 +134,7 @@ impl Timings {
             }
         }
 
-        data.sort_unstable_by(|a, b| a.day.cmp(&b.day));
+        data.sort_unstable_by_key(|a| a.day);
         Timings { data }
     }
 
@@ -133,6 +216,42 @@ impl From<&Timing> for JsonValue {
             },
         );
 
+        for (key, stats) in [
+            ("part_1_stats", &value.part_1_stats),
+            ("part_2_stats", &value.part_2_stats),
+        ] {
+            map.insert(
+                key.into(),
+                match stats {
+                    Some(x) => JsonValue::from(x),
+                    None => JsonValue::Null,
+                },
+            );
+        }
+
+        for (key, memory) in [
+            ("part_1_memory", &value.part_1_memory),
+            ("part_2_memory", &value.part_2_memory),
+        ] {
+            map.insert(
+                key.into(),
+                match memory {
+                    Some(x) => JsonValue::from(x),
+                    None => JsonValue::Null,
+                },
+            );
+        }
+
+        for (key, spans) in [
+            ("part_1_spans", &value.part_1_spans),
+            ("part_2_spans", &value.part_2_spans),
+        ] {
+            map.insert(
+                key.into(),
+                JsonValue::Array(spans.iter().map(JsonValue::from).collect()),
+            );
+        }
+
         JsonValue::Object(map)
     }
 }
@@ -166,10 +285,50 @@ impl TryFrom<&JsonValue> for Timing {
             .and_then(|v| v.get::<f64>().copied())
             .ok_or("Expected timing.total_nanos to be a number.")?;
 
+        // NOTE: statistics were added later, so treat a missing key like `null`.
+        let part_1_stats = match json.get("part_1_stats") {
+            Some(v) if !v.is_null() => Some(Statistics::try_from(v)?),
+            _ => None,
+        };
+
+        let part_2_stats = match json.get("part_2_stats") {
+            Some(v) if !v.is_null() => Some(Statistics::try_from(v)?),
+            _ => None,
+        };
+
+        let part_1_memory = match json.get("part_1_memory") {
+            Some(v) if !v.is_null() => Some(MemoryStats::try_from(v)?),
+            _ => None,
+        };
+
+        let part_2_memory = match json.get("part_2_memory") {
+            Some(v) if !v.is_null() => Some(MemoryStats::try_from(v)?),
+            _ => None,
+        };
+
+        let spans = |key: &str| match json.get(key) {
+            Some(v) if !v.is_null() => v
+                .get::<Vec<JsonValue>>()
+                .ok_or(format!("Expected timing.{key} to be an array."))?
+                .iter()
+                .map(SpanTiming::try_from)
+                .collect::<Result<Vec<_>, _>>(),
+            _ => Ok(vec![]),
+        };
+
+        let part_1_spans = spans("part_1_spans")?;
+        let part_2_spans = spans("part_2_spans")?;
+
         Ok(Timing {
             day,
             part_1: part_1.cloned(),
             part_2: part_2.cloned(),
+            part_1_stats,
+            part_2_stats,
+            part_1_memory,
+            part_2_memory,
+            part_1_spans,
+            part_2_spans,
             total_nanos,
         })
     }
@@ -177,6 +336,145 @@ impl TryFrom<&JsonValue> for Timing {
 
 /* -------------------------------------------------------------------------- */
 
+impl From<&Statistics> for JsonValue {
+    #[allow(clippy::cast_precision_loss)]
+    fn from(value: &Statistics) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("samples".into(), JsonValue::Number(value.samples as f64));
+        map.insert("mean_nanos".into(), JsonValue::Number(value.mean_nanos));
+        map.insert("median_nanos".into(), JsonValue::Number(value.median_nanos));
+        map.insert("min_nanos".into(), JsonValue::Number(value.min_nanos));
+        map.insert("max_nanos".into(), JsonValue::Number(value.max_nanos));
+        map.insert(
+            "std_dev_nanos".into(),
+            JsonValue::Number(value.std_dev_nanos),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for Statistics {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected statistics to be a JSON object.")?;
+
+        let number = |key: &str| {
+            json.get(key)
+                .and_then(|v| v.get::<f64>().copied())
+                .ok_or(format!("Expected statistics.{key} to be a number."))
+        };
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        Ok(Statistics {
+            samples: number("samples")? as u64,
+            mean_nanos: number("mean_nanos")?,
+            median_nanos: number("median_nanos")?,
+            min_nanos: number("min_nanos")?,
+            max_nanos: number("max_nanos")?,
+            std_dev_nanos: number("std_dev_nanos")?,
+        })
+    }
+}
+
+impl From<&MemoryStats> for JsonValue {
+    #[allow(clippy::cast_precision_loss)]
+    fn from(value: &MemoryStats) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "peak_bytes".into(),
+            JsonValue::Number(value.peak_bytes as f64),
+        );
+        map.insert(
+            "total_bytes".into(),
+            JsonValue::Number(value.total_bytes as f64),
+        );
+        map.insert(
+            "allocations".into(),
+            JsonValue::Number(value.allocations as f64),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for MemoryStats {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected memory stats to be a JSON object.")?;
+
+        let number = |key: &str| {
+            json.get(key)
+                .and_then(|v| v.get::<f64>().copied())
+                .ok_or(format!("Expected memory.{key} to be a number."))
+        };
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        Ok(MemoryStats {
+            peak_bytes: number("peak_bytes")? as u64,
+            total_bytes: number("total_bytes")? as u64,
+            allocations: number("allocations")? as u64,
+        })
+    }
+}
+
+impl From<&SpanTiming> for JsonValue {
+    #[allow(clippy::cast_precision_loss)]
+    fn from(value: &SpanTiming) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("name".into(), JsonValue::String(value.name.clone()));
+        map.insert("depth".into(), JsonValue::Number(value.depth as f64));
+        map.insert("nanos".into(), JsonValue::Number(value.nanos));
+        map.insert("calls".into(), JsonValue::Number(value.calls as f64));
+        if let Some(hits) = value.hits {
+            map.insert("hits".into(), JsonValue::Number(hits as f64));
+        }
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for SpanTiming {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected span to be a JSON object.")?;
+
+        let number = |key: &str| {
+            json.get(key)
+                .and_then(|v| v.get::<f64>().copied())
+                .ok_or(format!("Expected span.{key} to be a number."))
+        };
+
+        let name = json
+            .get("name")
+            .and_then(|v| v.get::<String>())
+            .ok_or("Expected span.name to be a string.")?;
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        Ok(SpanTiming {
+            name: name.clone(),
+            depth: number("depth")? as u64,
+            nanos: number("nanos")?,
+            calls: number("calls")? as u64,
+            hits: number("hits").ok().map(|hits| hits as u64),
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
 #[cfg(feature = "test_lib")]
 mod tests {
     use crate::day;
@@ -190,18 +488,36 @@ mod tests {
                     day: day!(1),
                     part_1: Some("10ms".into()),
                     part_2: Some("20ms".into()),
+                    part_1_stats: None,
+                    part_2_stats: None,
+                    part_1_memory: None,
+                    part_2_memory: None,
+                    part_1_spans: vec![],
+                    part_2_spans: vec![],
                     total_nanos: 3e+10,
                 },
                 Timing {
                     day: day!(2),
                     part_1: Some("30ms".into()),
                     part_2: Some("40ms".into()),
+                    part_1_stats: None,
+                    part_2_stats: None,
+                    part_1_memory: None,
+                    part_2_memory: None,
+                    part_1_spans: vec![],
+                    part_2_spans: vec![],
                     total_nanos: 7e+10,
                 },
                 Timing {
                     day: day!(4),
                     part_1: Some("40ms".into()),
                     part_2: None,
+                    part_1_stats: None,
+                    part_2_stats: None,
+                    part_1_memory: None,
+                    part_2_memory: None,
+                    part_1_spans: vec![],
+                    part_2_spans: vec![],
                     total_nanos: 4e+10,
                 },
             ],
@@ -209,7 +525,10 @@ mod tests {
     }
 
     mod deserialization {
-        use crate::{day, template::timings::Timings};
+        use crate::{
+            day,
+            template::timings::{SpanTiming, Timings},
+        };
 
         #[test]
         fn handles_json_timings() {
@@ -223,6 +542,24 @@ mod tests {
             assert_eq!(timing.total_nanos, 1_000_000_000_f64);
         }
 
+        #[test]
+        fn handles_json_spans() {
+            let json = r#"{ "data": [{ "day": "05", "part_1": null, "part_2": "1ms", "total_nanos": 1000000, "part_2_spans": [{ "name": "sort", "depth": 0, "nanos": 500000, "calls": 3 }] }] }"#.to_string();
+            let timings = Timings::try_from(json).unwrap();
+            let timing = timings.data.first().unwrap();
+            assert!(timing.part_1_spans.is_empty());
+            assert_eq!(
+                timing.part_2_spans,
+                vec![SpanTiming {
+                    name: "sort".into(),
+                    depth: 0,
+                    nanos: 500_000.0,
+                    calls: 3,
+                    hits: None,
+                }]
+            );
+        }
+
         #[test]
         fn handles_empty_timings() {
             let json = r#"{ "data": [] }"#.to_string();
@@ -281,11 +618,17 @@ mod tests {
                     day: day!(1),
                     part_1: Some("1ms".into()),
                     part_2: Some("2ms".into()),
+                    part_1_stats: None,
+                    part_2_stats: None,
+                    part_1_memory: None,
+                    part_2_memory: None,
+                    part_1_spans: vec![],
+                    part_2_spans: vec![],
                     total_nanos: 3_000_000_000_f64,
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), true);
+            assert_eq!(timings.is_day_complete(day!(1)), true);
         }
 
         #[test]
@@ -295,11 +638,17 @@ mod tests {
                     day: day!(1),
                     part_1: Some("1ms".into()),
                     part_2: None,
+                    part_1_stats: None,
+                    part_2_stats: None,
+                    part_1_memory: None,
+                    part_2_memory: None,
+                    part_1_spans: vec![],
+                    part_2_spans: vec![],
                     total_nanos: 1_000_000_000_f64,
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), false);
+            assert_eq!(timings.is_day_complete(day!(1)), false);
         }
 
         #[test]
@@ -309,11 +658,17 @@ mod tests {
                     day: day!(1),
                     part_1: None,
                     part_2: None,
+                    part_1_stats: None,
+                    part_2_stats: None,
+                    part_1_memory: None,
+                    part_2_memory: None,
+                    part_1_spans: vec![],
+                    part_2_spans: vec![],
                     total_nanos: 0.0,
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), false);
+            assert_eq!(timings.is_day_complete(day!(1)), false);
         }
     }
 
@@ -333,6 +688,12 @@ mod tests {
                     day: day!(3),
                     part_1: None,
                     part_2: None,
+                    part_1_stats: None,
+                    part_2_stats: None,
+                    part_1_memory: None,
+                    part_2_memory: None,
+                    part_1_spans: vec![],
+                    part_2_spans: vec![],
                     total_nanos: 0_f64,
                 }],
             };
@@ -353,6 +714,12 @@ mod tests {
                     day: day!(2),
                     part_1: None,
                     part_2: None,
+                    part_1_stats: None,
+                    part_2_stats: None,
+                    part_1_memory: None,
+                    part_2_memory: None,
+                    part_1_spans: vec![],
+                    part_2_spans: vec![],
                     total_nanos: 0_f64,
                 }],
             };
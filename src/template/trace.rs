@@ -0,0 +1,226 @@
+/// Records the execution of a day as a trace in the Chrome Trace Event format.
+///
+/// Tracing is enabled by passing `--trace <path>` to a solution, e.g. via `cargo solve <day> --trace trace.json`.
+/// The trace contains the phases of the runner (reading the input, running and benching each part),
+/// every bench iteration and the [`span!`](crate::span)s opened while running a part.
+/// It can be opened in a trace viewer such as `chrome://tracing` or [Perfetto](https://ui.perfetto.dev).
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::time::Instant;
+
+use tinyjson::JsonValue;
+
+thread_local! {
+    static TRACE: RefCell<Option<Trace>> = const { RefCell::new(None) };
+}
+
+struct Trace {
+    path: String,
+    origin: Instant,
+    events: Vec<Event>,
+    /// Whether spans are recorded. Spans are only recorded during the first run of a part, not while benching.
+    spans: bool,
+}
+
+/// A completed event of the trace.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    pub name: String,
+    pub category: &'static str,
+    /// Start of the event in microseconds since the trace started.
+    pub start_micros: f64,
+    pub duration_micros: f64,
+}
+
+/// Start tracing if `--trace <path>` was passed to the program.
+pub fn start_from_args() {
+    let args: Vec<String> = env::args().collect();
+
+    let Some(index) = args.iter().position(|x| x == "--trace") else {
+        return;
+    };
+
+    let Some(path) = args.get(index + 1) else {
+        eprintln!("Unexpected command-line input. Format: cargo solve 1 --trace trace.json");
+        std::process::exit(1);
+    };
+
+    start(path);
+}
+
+/// Start tracing on the current thread. The trace is written to `path` by [`finish`].
+pub fn start(path: &str) {
+    TRACE.set(Some(Trace {
+        path: path.into(),
+        origin: Instant::now(),
+        events: vec![],
+        spans: false,
+    }));
+}
+
+/// Returns `true` if the current thread is being traced.
+pub fn is_tracing() -> bool {
+    TRACE.with_borrow(Option::is_some)
+}
+
+/// Stop tracing and write the trace to the path passed to [`start`].
+pub fn finish() {
+    let Some(trace) = TRACE.take() else {
+        return;
+    };
+
+    let json = to_json(&trace.events);
+    match json.format() {
+        Ok(json) => match fs::write(&trace.path, json) {
+            Ok(()) => println!("Wrote trace to \"{}\".", trace.path),
+            Err(e) => eprintln!("Failed to write trace to \"{}\": {e}", trace.path),
+        },
+        Err(e) => eprintln!("Failed to serialize trace: {e}"),
+    }
+}
+
+/// Run `f` as a phase of the runner named `name`.
+pub fn phase<T>(name: &str, f: impl FnOnce() -> T) -> T {
+    if !is_tracing() {
+        return f();
+    }
+
+    let start = Instant::now();
+    let result = f();
+    record(name, "runner", start);
+    result
+}
+
+/// Run `f`, recording the spans it opens in the trace.
+pub(crate) fn with_spans<T>(f: impl FnOnce() -> T) -> T {
+    let previous = set_spans(true);
+    let result = f();
+    set_spans(previous);
+    result
+}
+
+fn set_spans(enabled: bool) -> bool {
+    TRACE.with_borrow_mut(|trace| {
+        trace
+            .as_mut()
+            .map(|t| std::mem::replace(&mut t.spans, enabled))
+            .unwrap_or_default()
+    })
+}
+
+/// Returns `true` if spans opened now should be recorded in the trace.
+pub(crate) fn is_recording_spans() -> bool {
+    TRACE.with_borrow(|trace| trace.as_ref().is_some_and(|t| t.spans))
+}
+
+/// Record a span that started at `start` and ends now.
+pub(crate) fn record_span(name: &str, start: Instant) {
+    if is_recording_spans() {
+        record(name, "span", start);
+    }
+}
+
+/// Record a completed event that started at `start` and ends now.
+pub(crate) fn record(name: &str, category: &'static str, start: Instant) {
+    let end = Instant::now();
+
+    TRACE.with_borrow_mut(|trace| {
+        if let Some(trace) = trace.as_mut() {
+            let start_micros = start.saturating_duration_since(trace.origin).as_secs_f64() * 1e6;
+            trace.events.push(Event {
+                name: name.into(),
+                category,
+                start_micros,
+                duration_micros: end.saturating_duration_since(start).as_secs_f64() * 1e6,
+            });
+        }
+    });
+}
+
+/// Serialize events to a Chrome Trace Event document.
+pub fn to_json(events: &[Event]) -> JsonValue {
+    let events = events
+        .iter()
+        .map(|event| {
+            let mut map: HashMap<String, JsonValue> = HashMap::new();
+            map.insert("name".into(), JsonValue::String(event.name.clone()));
+            map.insert("cat".into(), JsonValue::String(event.category.into()));
+            map.insert("ph".into(), JsonValue::String("X".into()));
+            map.insert("ts".into(), JsonValue::Number(event.start_micros));
+            map.insert("dur".into(), JsonValue::Number(event.duration_micros));
+            map.insert("pid".into(), JsonValue::Number(1.0));
+            map.insert("tid".into(), JsonValue::Number(1.0));
+            JsonValue::Object(map)
+        })
+        .collect();
+
+    let mut map: HashMap<String, JsonValue> = HashMap::new();
+    map.insert("traceEvents".into(), JsonValue::Array(events));
+    map.insert("displayTimeUnit".into(), JsonValue::String("ns".into()));
+    JsonValue::Object(map)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::collections::HashMap;
+
+    use tinyjson::JsonValue;
+
+    use super::{is_tracing, phase, start, to_json, with_spans, Event, TRACE};
+
+    fn events() -> Vec<Event> {
+        TRACE.with_borrow(|t| t.as_ref().unwrap().events.clone())
+    }
+
+    #[test]
+    fn records_phases_and_spans() {
+        assert!(!is_tracing());
+        start("unused.json");
+
+        phase("read input", || {});
+        crate::span!("ignored", ());
+        phase("Part 1", || with_spans(|| crate::span!("parse", ())));
+
+        let names: Vec<_> = events()
+            .iter()
+            .map(|e| (e.name.clone(), e.category))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("read input".into(), "runner"),
+                ("parse".into(), "span"),
+                ("Part 1".into(), "runner"),
+            ]
+        );
+
+        let events = events();
+        let (parse, part) = (&events[1], &events[2]);
+        assert!(parse.start_micros >= part.start_micros);
+        assert!(parse.duration_micros <= part.duration_micros);
+
+        TRACE.take();
+    }
+
+    #[test]
+    fn serializes_complete_events() {
+        let json = to_json(&[Event {
+            name: "Part 1".into(),
+            category: "runner",
+            start_micros: 1.5,
+            duration_micros: 10.0,
+        }]);
+
+        let json: &HashMap<String, JsonValue> = json.get().unwrap();
+        let events: &Vec<JsonValue> = json["traceEvents"].get().unwrap();
+        let event: &HashMap<String, JsonValue> = events[0].get().unwrap();
+
+        assert_eq!(event["ph"], JsonValue::String("X".into()));
+        assert_eq!(event["ts"], JsonValue::Number(1.5));
+        assert_eq!(event["dur"], JsonValue::Number(10.0));
+    }
+}
@@ -0,0 +1,463 @@
+//! Draws grid simulations to the terminal with ANSI colours.
+//!
+//! A solution draws [`Frame`]s, either one per step of a simulation or a list of snapshots with [`play`].
+//! Frames are only shown when the solution is run with `cargo solve <day> --visualize`,
+//! and written as images to `data/visuals/<day>/` with `--render`. Both build it with the `visualize` feature.
+//! Other images can be written with [`render`]. Drawing code goes into the [`visualize!`](crate::visualize) macro,
+//! which is compiled out without the feature, so benchmarks and `cargo time` never pay for it.
+//!
+//! ```
+//! use advent_of_code::grid::{Grid, Point};
+//! use advent_of_code::visual::{Cell, Color, Frame};
+//!
+//! let map = Grid::parse("..#\n...", |c| c == '#');
+//! let mut frame = Frame::from_grid(&map, |&wall| if wall { Cell::new('#', Color::Grey) } else { '.'.into() });
+//! frame.set(Point::new(0, 1), Cell::new('@', Color::Red));
+//! assert_eq!(frame.to_string(), "..#\n@..");
+//! ```
+use std::cell::RefCell;
+use std::env;
+use std::fmt::Display;
+use std::io::{stdout, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use crate::grid::{Bounds, Grid, Point, SparseGrid};
+use crate::image::{Animation, Image, ImageError, Rgb};
+use crate::template::Day;
+
+/// The size of a cell in rendered images, in pixels.
+pub const CELL_PIXELS: usize = 4;
+
+/// The colour of blank cells in rendered images.
+const BACKGROUND: Rgb = Rgb::new(0, 43, 54);
+
+/// The frames shown per second if no `--speed` is passed.
+pub const DEFAULT_SPEED: f64 = 20.0;
+
+thread_local! {
+    static PLAYER: RefCell<Option<Player>> = const { RefCell::new(None) };
+}
+
+/// A foreground colour of a cell.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Color {
+    #[default]
+    Default,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    Grey,
+}
+
+impl Color {
+    fn ansi(self) -> &'static str {
+        match self {
+            Self::Default => "\x1b[39m",
+            Self::Red => "\x1b[31m",
+            Self::Green => "\x1b[32m",
+            Self::Yellow => "\x1b[33m",
+            Self::Blue => "\x1b[34m",
+            Self::Magenta => "\x1b[35m",
+            Self::Cyan => "\x1b[36m",
+            Self::Grey => "\x1b[90m",
+        }
+    }
+
+    /// The colour in rendered images.
+    pub fn rgb(self) -> Rgb {
+        match self {
+            Self::Default => Rgb::new(238, 232, 213),
+            Self::Red => Rgb::new(220, 50, 47),
+            Self::Green => Rgb::new(133, 153, 0),
+            Self::Yellow => Rgb::new(181, 137, 0),
+            Self::Blue => Rgb::new(38, 139, 210),
+            Self::Magenta => Rgb::new(211, 54, 130),
+            Self::Cyan => Rgb::new(42, 161, 152),
+            Self::Grey => Rgb::new(88, 110, 117),
+        }
+    }
+}
+
+/// A character drawn in a colour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cell {
+    pub ch: char,
+    pub color: Color,
+}
+
+impl Cell {
+    pub const fn new(ch: char, color: Color) -> Self {
+        Self { ch, color }
+    }
+}
+
+impl From<char> for Cell {
+    fn from(ch: char) -> Self {
+        Self::new(ch, Color::Default)
+    }
+}
+
+/// A snapshot of a simulation, with an optional caption below the cells.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    cells: Grid<Cell>,
+    caption: String,
+}
+
+impl Frame {
+    /// A blank frame of `width` by `height` cells.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            cells: Grid::new(width, height, Cell::from(' ')),
+            caption: String::new(),
+        }
+    }
+
+    /// Draw every cell of `grid`.
+    pub fn from_grid<T>(grid: &Grid<T>, cell: impl FnMut(&T) -> Cell) -> Self {
+        Self {
+            cells: grid.map(cell),
+            caption: String::new(),
+        }
+    }
+
+    /// Draw the points of `grid` within `bounds`, unoccupied points are drawn as `empty`.
+    pub fn from_sparse<T>(
+        grid: &SparseGrid<T>,
+        bounds: Bounds,
+        empty: Cell,
+        mut cell: impl FnMut(&T) -> Cell,
+    ) -> Self {
+        let cells = bounds
+            .points()
+            .map(|point| grid.get(point).map_or(empty, &mut cell))
+            .collect();
+        Self {
+            cells: Grid::from_cells(bounds.width(), bounds.height(), cells),
+            caption: String::new(),
+        }
+    }
+
+    /// Set the text below the cells.
+    #[must_use]
+    pub fn caption(mut self, caption: impl Into<String>) -> Self {
+        self.caption = caption.into();
+        self
+    }
+
+    /// Draw a cell, points outside of the frame are ignored.
+    pub fn set(&mut self, point: Point, cell: impl Into<Cell>) {
+        if let Some(target) = self.cells.get_mut(point) {
+            *target = cell.into();
+        }
+    }
+
+    /// The number of lines the frame takes up in the terminal.
+    pub fn lines(&self) -> usize {
+        self.cells.height() + usize::from(!self.caption.is_empty())
+    }
+
+    /// The frame as an image of [`CELL_PIXELS`] pixels per cell, without the caption.
+    /// Cells drawn as ` ` or `.` are blank, other cells are filled with their colour.
+    pub fn to_image(&self) -> Image {
+        let cells = self.cells.map(|cell| match cell.ch {
+            ' ' | '.' => BACKGROUND,
+            _ => cell.color.rgb(),
+        });
+        Image::from_grid(&cells, |&color| color).scaled(CELL_PIXELS)
+    }
+
+    /// The frame with ANSI colour codes, which are only emitted where the colour changes.
+    /// Every line clears the rest of the terminal line, so that a frame can be drawn over a previous one.
+    pub fn to_ansi(&self) -> String {
+        let mut text = String::with_capacity(2 * (self.cells.width() + 8) * self.lines());
+        let mut color = Color::Default;
+
+        for row in self.cells.rows() {
+            for cell in row {
+                if cell.color != color {
+                    color = cell.color;
+                    text.push_str(color.ansi());
+                }
+                text.push(cell.ch);
+            }
+            text.push_str("\x1b[K\n");
+        }
+        text.push_str(Color::Default.ansi());
+
+        if !self.caption.is_empty() {
+            text.push_str(&self.caption);
+            text.push_str("\x1b[K\n");
+        }
+        text
+    }
+}
+
+/// The frame without colours.
+impl Display for Frame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = self
+            .cells
+            .rows()
+            .map(|row| row.iter().map(|cell| cell.ch).collect::<String>())
+            .chain((!self.caption.is_empty()).then(|| self.caption.clone()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        f.write_str(&text)
+    }
+}
+
+/// How a solution is visualised.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Config {
+    /// Draw frames to the terminal.
+    pub terminal: bool,
+    /// Write frames as images to `data/visuals/<day>/`.
+    pub render: bool,
+    /// Frames per second.
+    pub speed: f64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            terminal: true,
+            render: false,
+            speed: DEFAULT_SPEED,
+        }
+    }
+}
+
+/// Shows frames at a fixed speed, each over the previous one, and records them when rendering.
+struct Player {
+    config: Config,
+    /// Lines taken up by the last frame of the current animation.
+    lines: usize,
+    /// The directory images are written to.
+    dir: PathBuf,
+    /// The frames of the current animation, when rendering.
+    animation: Animation,
+}
+
+/// Start visualising if `--visualize` or `--render` was passed to the program, at `--speed <fps>` frames per second.
+pub fn start_from_args(day: Day) {
+    let args: Vec<String> = env::args().collect();
+
+    let terminal = args.iter().any(|x| x == "--visualize");
+    let render = args.iter().any(|x| x == "--render");
+    if !terminal && !render {
+        return;
+    }
+
+    if !cfg!(feature = "visualize") {
+        eprintln!(
+            "Visualisation is compiled out, run `cargo solve <day> --visualize` or `--render` to enable it."
+        );
+        return;
+    }
+
+    let speed = match args.iter().position(|x| x == "--speed") {
+        Some(index) => {
+            match args.get(index + 1).and_then(|x| x.parse::<f64>().ok()) {
+                Some(speed) if speed > 0.0 => speed,
+                _ => {
+                    eprintln!("Unexpected command-line input. Format: cargo solve 6 --visualize --speed 30");
+                    std::process::exit(1);
+                }
+            }
+        }
+        None => DEFAULT_SPEED,
+    };
+
+    start(
+        day,
+        Config {
+            terminal,
+            render,
+            speed,
+        },
+    );
+}
+
+/// Start visualising on the current thread.
+pub fn start(day: Day, config: Config) {
+    let delay = Duration::from_secs_f64(1.0 / config.speed);
+    PLAYER.set(Some(Player {
+        config,
+        lines: 0,
+        dir: Path::new("data").join("visuals").join(day.to_string()),
+        animation: Animation::new(delay),
+    }));
+    if config.terminal {
+        print!("\x1b[?25l");
+    }
+}
+
+/// Returns `true` if frames are shown or rendered on the current thread.
+pub fn is_enabled() -> bool {
+    PLAYER.with_borrow(Option::is_some)
+}
+
+/// Draw a frame over the previous frame of the current animation and wait for the next one.
+/// When rendering, the frame is added to the animation written by [`cut`].
+pub fn show(frame: &Frame) {
+    PLAYER.with_borrow_mut(|player| {
+        let Some(player) = player.as_mut() else {
+            return;
+        };
+
+        if player.config.render {
+            player.animation.push(frame.to_image());
+        }
+
+        if player.config.terminal {
+            let mut out = stdout().lock();
+            if player.lines > 0 {
+                let _ = write!(out, "\x1b[{}A\r", player.lines);
+            }
+            let _ = out.write_all(frame.to_ansi().as_bytes());
+            let _ = out.flush();
+            player.lines = frame.lines();
+
+            thread::sleep(Duration::from_secs_f64(1.0 / player.config.speed));
+        }
+    });
+}
+
+/// Show a sequence of snapshots.
+pub fn play(frames: impl IntoIterator<Item = Frame>) {
+    for frame in frames {
+        show(&frame);
+    }
+}
+
+/// End the current animation, so that output and the next frame are drawn below its last frame.
+/// When rendering, the animation is written to `<name>.gif` and its last frame to `<name>.png`.
+pub fn cut(name: &str) {
+    let animation = PLAYER.with_borrow_mut(|player| {
+        let player = player.as_mut()?;
+        player.lines = 0;
+        let delay = Duration::from_secs_f64(1.0 / player.config.speed);
+        let animation = std::mem::replace(&mut player.animation, Animation::new(delay));
+        (!animation.is_empty()).then_some(animation)
+    });
+
+    if let Some(animation) = animation {
+        render(name, animation.frames().last().unwrap());
+        if animation.len() > 1 {
+            render_animation(name, &animation);
+        }
+    }
+}
+
+/// Write an image to `data/visuals/<day>/<name>.png`, if the solution is rendered.
+pub fn render(name: &str, image: &Image) {
+    save(&format!("{name}.png"), |path| image.save_png(path));
+}
+
+/// Write an animation to `data/visuals/<day>/<name>.gif`, if the solution is rendered.
+pub fn render_animation(name: &str, animation: &Animation) {
+    save(&format!("{name}.gif"), |path| animation.save_gif(path));
+}
+
+fn save(file: &str, write: impl FnOnce(&Path) -> Result<(), ImageError>) {
+    let path = PLAYER.with_borrow(|player| {
+        player
+            .as_ref()
+            .filter(|player| player.config.render)
+            .map(|player| player.dir.join(file))
+    });
+
+    if let Some(path) = path {
+        match write(&path) {
+            Ok(()) => println!("Wrote \"{}\".", path.display()),
+            Err(e) => eprintln!("Failed to write \"{}\": {e}", path.display()),
+        }
+    }
+}
+
+/// Stop visualising and restore the cursor.
+pub fn finish() {
+    if let Some(player) = PLAYER.take() {
+        if player.config.terminal {
+            print!("\x1b[?25h");
+            let _ = stdout().flush();
+        }
+    }
+}
+
+/// Runs the statements only when the solution is visualised with `cargo solve <day> --visualize` or `--render`.
+/// Without the `visualize` feature, the statements are removed at compile time.
+///
+/// ```ignore
+/// advent_of_code::visualize! {
+///     advent_of_code::visual::show(&frame(&map, guard));
+/// }
+/// ```
+#[cfg(feature = "visualize")]
+#[macro_export]
+macro_rules! visualize {
+    ($($body:tt)*) => {
+        if $crate::visual::is_enabled() {
+            $($body)*
+        }
+    };
+}
+
+/// Runs the statements only when the solution is visualised with `cargo solve <day> --visualize` or `--render`.
+/// Without the `visualize` feature, the statements are removed at compile time.
+#[cfg(not(feature = "visualize"))]
+#[macro_export]
+macro_rules! visualize {
+    ($($body:tt)*) => {};
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Cell, Color, Frame};
+    use crate::grid::{Bounds, Point, SparseGrid};
+
+    #[test]
+    fn draws_cells() {
+        let mut frame = Frame::new(3, 2).caption("step 1");
+        frame.set(Point::new(1, 0), '#');
+        frame.set(Point::new(2, 1), Cell::new('@', Color::Red));
+        frame.set(Point::new(3, 1), 'x');
+
+        assert_eq!(frame.to_string(), " # \n  @\nstep 1");
+        assert_eq!(frame.lines(), 3);
+    }
+
+    #[test]
+    fn colours_only_change_between_cells() {
+        let mut frame = Frame::new(3, 1);
+        frame.set(Point::new(0, 0), Cell::new('a', Color::Red));
+        frame.set(Point::new(1, 0), Cell::new('b', Color::Red));
+
+        assert_eq!(frame.to_ansi(), "\x1b[31mab\x1b[39m \x1b[K\n\x1b[39m");
+    }
+
+    #[test]
+    fn draws_sparse_grids() {
+        let grid: SparseGrid<char> = [(Point::new(-1, 0), 'a'), (Point::new(1, 1), 'b')]
+            .into_iter()
+            .collect();
+        let frame = Frame::from_sparse(&grid, grid.bounds().unwrap(), '.'.into(), |&c| c.into());
+        assert_eq!(frame.to_string(), "a..\n..b");
+
+        let frame = Frame::from_sparse(
+            &grid,
+            Bounds::new(Point::ZERO, Point::new(1, 0)),
+            ' '.into(),
+            |&c| c.into(),
+        );
+        assert_eq!(frame.to_string(), "  ");
+    }
+}
@@ -0,0 +1,407 @@
+//! A small interpreter for puzzles that simulate a machine running a program.
+//!
+//! A puzzle defines its instruction set as a type implementing [`Instruction`], which updates the
+//! [`Registers`] and tells the [`Machine`] where to continue with an [`Effect`].
+//! Instruction sets written as assembly also implement [`Decode`] to be parsed with [`Machine::parse`].
+//! The machine halts when an instruction halts it or the program counter leaves the program.
+//!
+//! ```
+//! use advent_of_code::vm::{Effect, Instruction, Machine, Register, Registers, Stop};
+//!
+//! #[derive(Debug)]
+//! enum Op {
+//!     Add(Register, i64),
+//!     Out(Register),
+//! }
+//!
+//! impl Instruction for Op {
+//!     fn execute(&self, registers: &mut Registers) -> Effect {
+//!         match *self {
+//!             Op::Add(register, value) => {
+//!                 registers[register] += value;
+//!                 Effect::Next
+//!             }
+//!             Op::Out(register) => Effect::Output(registers[register]),
+//!         }
+//!     }
+//! }
+//!
+//! let mut registers = Registers::new();
+//! let a = registers.register("a");
+//! let mut machine = Machine::new(vec![Op::Add(a, 2), Op::Out(a), Op::Add(a, 3), Op::Out(a)], registers);
+//! assert_eq!(machine.run(), Stop::Halted);
+//! assert_eq!(machine.output(), [2, 5]);
+//! ```
+use std::fmt::{Debug, Display};
+
+use crate::hash::FastSet;
+
+mod parse;
+mod registers;
+
+pub use parse::{parse_program, Decode, ParseError};
+pub use registers::{Operand, Register, Registers};
+
+/// An instruction of a machine.
+pub trait Instruction: Debug {
+    fn execute(&self, registers: &mut Registers) -> Effect;
+}
+
+/// Where the machine continues after an instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Effect {
+    /// Continue with the next instruction.
+    Next,
+    /// Jump by an offset relative to the instruction.
+    Jump(i64),
+    /// Jump to an instruction.
+    JumpTo(usize),
+    /// Output a value and continue with the next instruction.
+    Output(i64),
+    Halt,
+}
+
+/// Why the machine stopped running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stop {
+    Halted,
+    /// The machine is about to execute the instruction at a breakpoint.
+    Breakpoint(usize),
+    /// An instruction output a value.
+    Output(i64),
+}
+
+/// An executed instruction, recorded in trace mode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceEntry {
+    /// The number of instructions executed so far, including this one.
+    pub step: u64,
+    pub pc: usize,
+    pub instruction: String,
+    /// The registers after the instruction.
+    pub registers: String,
+}
+
+impl Display for TraceEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:>6} {:>4}: {:<24} {}",
+            self.step, self.pc, self.instruction, self.registers
+        )
+    }
+}
+
+/// Runs a program of instructions.
+#[derive(Debug, Clone)]
+pub struct Machine<I> {
+    program: Vec<I>,
+    pub registers: Registers,
+    pc: usize,
+    halted: bool,
+    steps: u64,
+    output: Vec<i64>,
+    breakpoints: FastSet<usize>,
+    /// The breakpoint that was reported last, which is executed when running again.
+    paused_at: Option<usize>,
+    trace: Option<Vec<TraceEntry>>,
+}
+
+impl<I: Instruction> Machine<I> {
+    pub fn new(program: Vec<I>, registers: Registers) -> Self {
+        Self {
+            halted: program.is_empty(),
+            program,
+            registers,
+            pc: 0,
+            steps: 0,
+            output: vec![],
+            breakpoints: FastSet::default(),
+            paused_at: None,
+            trace: None,
+        }
+    }
+
+    /// Parse a program with one instruction per line, see [`parse_program`].
+    pub fn parse(text: &str) -> Result<Self, ParseError>
+    where
+        I: Decode,
+    {
+        let mut registers = Registers::new();
+        let program = parse_program(text, &mut registers)?;
+        Ok(Self::new(program, registers))
+    }
+
+    pub fn program(&self) -> &[I] {
+        &self.program
+    }
+
+    /// The index of the next instruction.
+    pub fn pc(&self) -> usize {
+        self.pc
+    }
+
+    /// Continue at instruction `pc`, halting if it is outside of the program.
+    pub fn set_pc(&mut self, pc: usize) {
+        self.pc = pc;
+        self.halted = pc >= self.program.len();
+        self.paused_at = None;
+    }
+
+    /// The number of instructions executed.
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.halted
+    }
+
+    /// All values output so far.
+    pub fn output(&self) -> &[i64] {
+        &self.output
+    }
+
+    /// Stop running before the instruction at `pc` is executed.
+    pub fn add_breakpoint(&mut self, pc: usize) {
+        self.breakpoints.insert(pc);
+    }
+
+    pub fn remove_breakpoint(&mut self, pc: usize) {
+        self.breakpoints.remove(&pc);
+    }
+
+    /// Record every executed instruction, see [`take_trace`](Self::take_trace).
+    pub fn trace(&mut self) {
+        self.trace.get_or_insert_with(Vec::new);
+    }
+
+    /// The instructions executed since the last call, empty if not tracing.
+    pub fn take_trace(&mut self) -> Vec<TraceEntry> {
+        self.trace.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    /// Start over from the first instruction, with all registers set to zero.
+    pub fn reset(&mut self) {
+        self.registers.reset();
+        self.set_pc(0);
+        self.steps = 0;
+        self.output.clear();
+        self.take_trace();
+    }
+
+    /// Execute a single instruction, ignoring breakpoints.
+    /// Returns `None` if the machine can continue without anything to report.
+    pub fn step(&mut self) -> Option<Stop> {
+        if self.halted {
+            return Some(Stop::Halted);
+        }
+
+        let pc = self.pc;
+        self.paused_at = None;
+        let instruction = &self.program[pc];
+        let effect = instruction.execute(&mut self.registers);
+        self.steps += 1;
+
+        if let Some(trace) = &mut self.trace {
+            trace.push(TraceEntry {
+                step: self.steps,
+                pc,
+                instruction: format!("{instruction:?}"),
+                registers: self.registers.to_string(),
+            });
+        }
+
+        let next = match effect {
+            Effect::Next | Effect::Output(_) => Some(pc + 1),
+            Effect::Jump(offset) => isize::try_from(offset)
+                .ok()
+                .and_then(|offset| pc.checked_add_signed(offset)),
+            Effect::JumpTo(target) => Some(target),
+            Effect::Halt => None,
+        };
+        match next.filter(|&next| next < self.program.len()) {
+            Some(next) => self.pc = next,
+            None => self.halted = true,
+        }
+
+        match effect {
+            Effect::Output(value) => {
+                self.output.push(value);
+                Some(Stop::Output(value))
+            }
+            _ if self.halted => Some(Stop::Halted),
+            _ => None,
+        }
+    }
+
+    /// Run until the machine halts or reaches a breakpoint. Output is collected in [`output`](Self::output).
+    pub fn run(&mut self) -> Stop {
+        self.resume(false)
+    }
+
+    /// Run until an instruction outputs a value, the machine halts or reaches a breakpoint.
+    pub fn run_until_output(&mut self) -> Stop {
+        self.resume(true)
+    }
+
+    fn resume(&mut self, stop_on_output: bool) -> Stop {
+        loop {
+            let paused = self.paused_at == Some(self.pc);
+            if !self.halted && !paused && self.breakpoints.contains(&self.pc) {
+                self.paused_at = Some(self.pc);
+                return Stop::Breakpoint(self.pc);
+            }
+
+            match self.step() {
+                Some(Stop::Output(value)) if stop_on_output => return Stop::Output(value),
+                Some(Stop::Halted) => return Stop::Halted,
+                _ => {}
+            }
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{
+        Decode, Effect, Instruction, Machine, Operand, ParseError, Register, Registers, Stop,
+    };
+
+    /// A small assembly language with copies, increments and conditional jumps.
+    #[derive(Debug)]
+    #[cfg_attr(not(test), allow(dead_code))]
+    enum Asm {
+        Cpy(Operand, Register),
+        Inc(Register),
+        Dec(Register),
+        Jnz(Operand, Operand),
+        Out(Operand),
+    }
+
+    impl Instruction for Asm {
+        fn execute(&self, registers: &mut Registers) -> Effect {
+            match *self {
+                Asm::Cpy(value, register) => registers[register] = value.get(registers),
+                Asm::Inc(register) => registers[register] += 1,
+                Asm::Dec(register) => registers[register] -= 1,
+                Asm::Jnz(value, offset) if value.get(registers) != 0 => {
+                    return Effect::Jump(offset.get(registers))
+                }
+                Asm::Jnz(..) => {}
+                Asm::Out(value) => return Effect::Output(value.get(registers)),
+            }
+            Effect::Next
+        }
+    }
+
+    impl Decode for Asm {
+        fn decode(
+            mnemonic: &str,
+            operands: &[&str],
+            registers: &mut Registers,
+        ) -> Result<Self, String> {
+            let mut operand = |i: usize| {
+                let text = operands.get(i).ok_or("missing operand")?;
+                Operand::parse(text, registers)
+            };
+            let register = |operand: Operand| match operand {
+                Operand::Register(register) => Ok(register),
+                Operand::Value(_) => Err("expected a register".to_string()),
+            };
+
+            Ok(match mnemonic {
+                "cpy" => Asm::Cpy(operand(0)?, register(operand(1)?)?),
+                "inc" => Asm::Inc(register(operand(0)?)?),
+                "dec" => Asm::Dec(register(operand(0)?)?),
+                "jnz" => Asm::Jnz(operand(0)?, operand(1)?),
+                "out" => Asm::Out(operand(0)?),
+                _ => return Err(format!("unknown instruction {mnemonic:?}")),
+            })
+        }
+    }
+
+    #[test]
+    fn runs_until_halted() {
+        let mut machine: Machine<Asm> =
+            Machine::parse("cpy 41 a\ninc a\ninc a\ndec a\njnz a 2\ndec a\n").unwrap();
+        assert_eq!(machine.run(), Stop::Halted);
+        assert_eq!(machine.registers.get("a"), Some(42));
+        assert_eq!(machine.steps(), 5);
+        assert_eq!(machine.run(), Stop::Halted);
+
+        machine.reset();
+        assert_eq!(machine.registers.get("a"), Some(0));
+        assert_eq!(machine.step(), None);
+        assert_eq!(machine.registers.get("a"), Some(41));
+    }
+
+    #[test]
+    fn stops_at_outputs_and_breakpoints() {
+        let mut machine: Machine<Asm> = Machine::parse("cpy 3, a\nout a\ndec a\njnz a -2").unwrap();
+        assert_eq!(machine.run_until_output(), Stop::Output(3));
+        assert_eq!(machine.run_until_output(), Stop::Output(2));
+
+        machine.add_breakpoint(1);
+        assert_eq!(machine.run_until_output(), Stop::Breakpoint(1));
+        assert_eq!(machine.run_until_output(), Stop::Output(1));
+        assert_eq!(machine.run(), Stop::Halted);
+        assert_eq!(machine.output(), [3, 2, 1]);
+
+        machine.reset();
+        machine.remove_breakpoint(1);
+        assert_eq!(machine.run(), Stop::Halted);
+        assert_eq!(machine.output(), [3, 2, 1]);
+    }
+
+    #[test]
+    fn stops_at_breakpoints_at_start_and_after_outputs() {
+        let mut machine: Machine<Asm> = Machine::parse("cpy 2 a\nout a\ninc a\nout a").unwrap();
+        machine.add_breakpoint(0);
+        machine.add_breakpoint(2);
+        assert_eq!(machine.run(), Stop::Breakpoint(0));
+        assert_eq!(machine.run_until_output(), Stop::Output(2));
+        assert_eq!(machine.pc(), 2);
+        assert_eq!(machine.run_until_output(), Stop::Breakpoint(2));
+        assert_eq!(machine.run_until_output(), Stop::Output(3));
+        assert_eq!(machine.run(), Stop::Halted);
+
+        machine.reset();
+        assert_eq!(machine.run(), Stop::Breakpoint(0));
+        assert_eq!(machine.step(), None);
+        machine.set_pc(0);
+        assert_eq!(machine.run(), Stop::Breakpoint(0));
+    }
+
+    #[test]
+    fn records_trace() {
+        let mut machine: Machine<Asm> = Machine::parse("cpy 2 a\ncpy a b\ndec a").unwrap();
+        machine.trace();
+        machine.run();
+
+        let trace = machine.take_trace();
+        assert_eq!(trace.len(), 3);
+        assert_eq!((trace[1].step, trace[1].pc), (2, 1));
+        assert_eq!(trace[2].registers, "a=1 b=2");
+        assert_eq!(trace[2].instruction, "Dec(Register(0))");
+        assert!(machine.take_trace().is_empty());
+    }
+
+    #[test]
+    fn reports_parse_errors() {
+        let error = Machine::<Asm>::parse("inc a\n\nhlt").unwrap_err();
+        assert_eq!(
+            error,
+            ParseError {
+                line: 3,
+                text: "hlt".to_string(),
+                message: "unknown instruction \"hlt\"".to_string(),
+            }
+        );
+        assert!(Machine::<Asm>::parse("inc 5").is_err());
+        assert!(Machine::<Asm>::parse("cpy 1").is_err());
+        assert!(Machine::<Asm>::parse("cpy 1 a-b").is_err());
+    }
+}
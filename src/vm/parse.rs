@@ -0,0 +1,54 @@
+use std::error::Error;
+use std::fmt::Display;
+
+use super::{Instruction, Registers};
+
+/// An instruction set that is written as assembly, one instruction per line.
+pub trait Decode: Instruction + Sized {
+    /// Decode an instruction from its mnemonic and operands, e.g. `jnz` and `["a", "-2"]`.
+    /// Registers named by operands are resolved with [`Operand::parse`](super::Operand::parse).
+    fn decode(mnemonic: &str, operands: &[&str], registers: &mut Registers)
+        -> Result<Self, String>;
+}
+
+/// An instruction that could not be decoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// The line of the instruction, starting at 1.
+    pub line: usize,
+    pub text: String,
+    pub message: String,
+}
+
+impl Error for ParseError {}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {} ({:?}): {}", self.line, self.text, self.message)
+    }
+}
+
+/// Parse a program with one instruction per line. Operands are separated by whitespace or commas.
+/// Empty lines are skipped.
+pub fn parse_program<I: Decode>(
+    text: &str,
+    registers: &mut Registers,
+) -> Result<Vec<I>, ParseError> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            let mut words = line
+                .split(|c: char| c.is_whitespace() || c == ',')
+                .filter(|word| !word.is_empty());
+            let mnemonic = words.next().unwrap_or_default();
+            let operands: Vec<&str> = words.collect();
+
+            I::decode(mnemonic, &operands, registers).map_err(|message| ParseError {
+                line: i + 1,
+                text: line.to_string(),
+                message,
+            })
+        })
+        .collect()
+}
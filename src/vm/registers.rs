@@ -0,0 +1,126 @@
+use std::fmt::Display;
+use std::ops::{Index, IndexMut};
+
+/// The index of a register, resolved from its name when a program is parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Register(pub usize);
+
+/// Named integer registers, all zero initially.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Registers {
+    names: Vec<String>,
+    values: Vec<i64>,
+}
+
+impl Registers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers with the given names, numbered in order.
+    pub fn with_names(names: &[&str]) -> Self {
+        let mut registers = Self::new();
+        for name in names {
+            registers.register(name);
+        }
+        registers
+    }
+
+    /// The register named `name`, added if it does not exist yet.
+    pub fn register(&mut self, name: &str) -> Register {
+        match self.find(name) {
+            Some(register) => register,
+            None => {
+                self.names.push(name.to_string());
+                self.values.push(0);
+                Register(self.values.len() - 1)
+            }
+        }
+    }
+
+    /// The register named `name`, if it exists.
+    pub fn find(&self, name: &str) -> Option<Register> {
+        self.names.iter().position(|n| n == name).map(Register)
+    }
+
+    /// The value of the register named `name`, if it exists.
+    pub fn get(&self, name: &str) -> Option<i64> {
+        self.find(name).map(|register| self[register])
+    }
+
+    pub fn name(&self, register: Register) -> &str {
+        &self.names[register.0]
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// The values in the order of the registers.
+    pub fn values(&self) -> &[i64] {
+        &self.values
+    }
+
+    /// Set every register to zero.
+    pub fn reset(&mut self) {
+        self.values.fill(0);
+    }
+}
+
+impl Index<Register> for Registers {
+    type Output = i64;
+
+    fn index(&self, register: Register) -> &i64 {
+        &self.values[register.0]
+    }
+}
+
+impl IndexMut<Register> for Registers {
+    fn index_mut(&mut self, register: Register) -> &mut i64 {
+        &mut self.values[register.0]
+    }
+}
+
+/// Formats the registers as `a=1 b=2`.
+impl Display for Registers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let registers: Vec<String> = self
+            .names
+            .iter()
+            .zip(&self.values)
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect();
+        f.write_str(&registers.join(" "))
+    }
+}
+
+/// An operand of an instruction, either a register or an immediate value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operand {
+    Register(Register),
+    Value(i64),
+}
+
+impl Operand {
+    /// Parse a number as a value and anything else as the name of a register, which is added if needed.
+    pub fn parse(text: &str, registers: &mut Registers) -> Result<Self, String> {
+        if let Ok(value) = text.parse() {
+            return Ok(Self::Value(value));
+        }
+        if text.is_empty() || !text.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return Err(format!("expected a number or a register, got {text:?}"));
+        }
+        Ok(Self::Register(registers.register(text)))
+    }
+
+    pub fn get(&self, registers: &Registers) -> i64 {
+        match *self {
+            Self::Register(register) => registers[register],
+            Self::Value(value) => value,
+        }
+    }
+}